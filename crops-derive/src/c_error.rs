@@ -0,0 +1,147 @@
+use proc_macro::TokenStream;
+use quote::format_ident;
+use syn::{
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Attribute, DataEnum, Fields, Ident, Meta, Variant, Visibility,
+};
+
+/// Mirrors `crops::error::CROPS_USER_ERROR_START`, the generated code asserts they match
+const USER_ERROR_START: i32 = 100;
+
+#[derive(Default)]
+struct CErrorVariantArgs {
+    code: Option<i32>,
+    transparent: bool,
+}
+
+impl Parse for CErrorVariantArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut res = Self::default();
+        let result = Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated(input)?;
+
+        for expr in result {
+            if let syn::Expr::Path(p) = expr {
+                if p.path.is_ident("transparent") {
+                    res.transparent = true;
+                }
+            } else if let syn::Expr::Assign(assign) = expr {
+                if let syn::Expr::Path(p) = assign.left.as_ref() {
+                    if p.path.is_ident("code") {
+                        res.code.replace(parse_code(assign.right.as_ref())?);
+                    }
+                }
+            }
+        }
+
+        Ok(res)
+    }
+}
+
+fn parse_code(expr: &syn::Expr) -> syn::Result<i32> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(int),
+            ..
+        }) => int.base10_parse(),
+        _ => Err(syn::Error::new_spanned(
+            expr,
+            "Error codes must be integer literals",
+        )),
+    }
+}
+
+fn variant_args(attrs: &[Attribute]) -> CErrorVariantArgs {
+    attrs
+        .iter()
+        .find_map(|attr| match &attr.meta {
+            Meta::List(list) if list.path.is_ident("c_error") => {
+                Some(syn::parse::<CErrorVariantArgs>(list.tokens.clone().into()).unwrap())
+            }
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn derive_c_error(ident: Ident, vis: Visibility, e: DataEnum) -> TokenStream {
+    let DataEnum { variants, .. } = e;
+
+    let code_ident = format_ident!("{ident}Code");
+
+    let mut next_code = USER_ERROR_START;
+    let mut codes = vec![];
+    let mut var_idents = vec![];
+    let mut arms = vec![];
+
+    for variant in variants.iter() {
+        let Variant {
+            attrs,
+            ident: var_ident,
+            fields,
+            discriminant,
+        } = variant;
+
+        let args = variant_args(attrs);
+
+        if args.transparent {
+            if !matches!(fields, Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1) {
+                panic!("Transparent errors must have a single unnamed field");
+            }
+
+            arms.push(quote::quote!(
+                #ident::#var_ident(inner) => ::crops::error::CError::code(inner)
+            ));
+            continue;
+        }
+
+        let code = match (args.code, discriminant) {
+            (Some(code), _) => code,
+            (None, Some((_, expr))) => parse_code(expr).unwrap(),
+            (None, None) => next_code,
+        };
+        next_code = code + 1;
+
+        let literal = proc_macro2::Literal::i32_unsuffixed(code);
+        codes.push(quote::quote!(#var_ident = #literal));
+        var_idents.push(var_ident);
+        arms.push(quote::quote!(
+            #ident::#var_ident { .. } => #code_ident::#var_ident as i32
+        ));
+    }
+
+    let code_enum = (!codes.is_empty()).then(|| {
+        quote::quote!(
+            /// ------
+            /// Error codes that can be returned in place of a crops error code
+            /// ------
+            ///
+            /// cbindgen:prefix-with-name
+            #[repr(C)]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #vis enum #code_ident {
+                #(#codes),*
+            }
+
+            const _: () = {
+                assert!(#USER_ERROR_START == ::crops::error::CROPS_USER_ERROR_START);
+                #(assert!(
+                    #code_ident::#var_idents as i32 >= ::crops::error::CROPS_USER_ERROR_START,
+                    "Error codes below CROPS_USER_ERROR_START are reserved by crops"
+                );)*
+            };
+        )
+    });
+
+    quote::quote!(
+        #code_enum
+
+        impl ::crops::error::CError for #ident {
+            fn code(&self) -> i32 {
+                match self {
+                    #(#arms),*
+                }
+            }
+        }
+    )
+    .into()
+}
@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+
+use convert_case::{Case, Casing};
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::format_ident;
+use syn::{
+    visit_mut::VisitMut, FnArg, ImplItem, ImplItemFn, ItemImpl, Pat, ReturnType, Type, Visibility,
+};
+
+use crate::{
+    arg_params, filter_args, gen_c_types_inner, get_wrapper_ty_ident, parse_angle_brackets, CTypes,
+    Substitute,
+};
+
+/// The value and error types of a method's return type
+fn split_output(output: &ReturnType) -> (Option<Type>, Option<Type>) {
+    let ty = match output {
+        ReturnType::Default => return (None, None),
+        ReturnType::Type(_, ty) => ty.as_ref(),
+    };
+
+    if let Type::Path(path) = ty {
+        let last = path.path.segments.last().unwrap();
+        let unit = |ty: &Type| !matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty());
+
+        match (
+            last.ident.to_string().as_str(),
+            parse_angle_brackets(last).as_slice(),
+        ) {
+            ("Result", [value, error]) => {
+                return (Some((*value).clone()).filter(unit), Some((*error).clone()))
+            }
+            ("CResult", []) => return (None, Some(syn::parse_quote!(::crops::error::Error))),
+            ("CResult", [error]) => return (None, Some((*error).clone())),
+            _ => {}
+        }
+    }
+
+    (Some(ty.clone()), None)
+}
+
+fn method_api(self_ty: &Type, type_name: &str, method: &ImplItemFn) -> TokenStream2 {
+    let mut sig = method.sig.clone();
+    if !sig.generics.params.is_empty() {
+        panic!("Generic methods are not supported by c_impl");
+    }
+    Substitute(HashMap::from([(format_ident!("Self"), self_ty.clone())]))
+        .visit_signature_mut(&mut sig);
+
+    let method_ident = &sig.ident;
+    let fn_ident = format_ident!(
+        "{type_name}_{}",
+        method_ident.to_string().to_case(Case::Snake),
+        span = method_ident.span()
+    );
+
+    let mut params = vec![];
+    let mut conversions = vec![];
+    let mut names = vec![];
+    let mut receiver = None;
+
+    for input in sig.inputs.iter() {
+        let typed = match input {
+            FnArg::Receiver(recv) => {
+                receiver = Some(match (&recv.reference, &recv.mutability) {
+                    (Some(_), Some(_)) => quote::quote!(::crops::utils::check_null(source)),
+                    (Some(_), None) => quote::quote!(::crops::utils::check_null_const(source)),
+                    (None, _) => panic!("c_impl methods must take &self or &mut self"),
+                });
+                params.push(match recv.mutability {
+                    Some(_) => quote::quote!(source: *mut #self_ty),
+                    None => quote::quote!(source: *const #self_ty),
+                });
+                continue;
+            }
+            FnArg::Typed(typed) => typed,
+        };
+
+        let Pat::Ident(pat) = typed.pat.as_ref() else {
+            panic!("c_impl arguments must be plain identifiers");
+        };
+        let name = &pat.ident;
+        let ty = typed.ty.as_ref();
+        let (args, _) = filter_args(&typed.attrs);
+        names.push(name.clone());
+
+        // Arguments `c_result_fn` knows how to receive, such as strings, references and foreign
+        // values, are left for it to convert, along with their `#[c_builder(..)]` attributes
+        let attrs = &typed.attrs;
+        if arg_params(name, ty, &args).is_some() {
+            params.push(quote::quote!(#(#attrs)* #name: #ty));
+            continue;
+        }
+
+        // Any other plain type is received like the value of a `with` setter
+        let Type::Path(path) = ty else {
+            params.push(quote::quote!(#name: #ty));
+            continue;
+        };
+        if !parse_angle_brackets(path.path.segments.last().unwrap()).is_empty() {
+            return syn::Error::new_spanned(ty, "This argument type is not supported by c_impl")
+                .to_compile_error();
+        }
+
+        let CTypes {
+            from_c,
+            from_c_parser,
+            ..
+        } = gen_c_types_inner(&args, ty);
+        let parser = from_c_parser(name);
+
+        params.push(quote::quote!(#name: #from_c));
+        conversions.push(quote::quote!(
+            let #name = {
+                let #name #parser;
+                #name
+            };
+        ));
+    }
+
+    let (value_ty, error_ty) = split_output(&sig.output);
+
+    // Errors of the method itself are kept apart from those of the checks around it
+    let (error, propagate) = match &error_ty {
+        Some(error) => (
+            quote::quote!(::crops::error::WrappedError<#error>),
+            quote::quote!(.map_err(::crops::error::WrappedError::User)?),
+        ),
+        None => (quote::quote!(::crops::error::Error), quote::quote!()),
+    };
+
+    let call = match &receiver {
+        Some(receiver) => quote::quote!(
+            #receiver
+                .map_err(|e| e.context(stringify!(#self_ty)))?
+                .#method_ident(#(#names),*)
+        ),
+        None => quote::quote!(#self_ty::#method_ident(#(#names),*)),
+    };
+
+    // The value is returned to C by `c_result_fn`, references as an owned copy of what they point to
+    let (value, value_ty) = match &value_ty {
+        Some(Type::Reference(reference)) => (
+            quote::quote!(::std::borrow::ToOwned::to_owned(#call #propagate)),
+            match reference.elem.as_ref() {
+                Type::Path(path) if path.path.is_ident("str") => syn::parse_quote!(String),
+                elem => elem.clone(),
+            },
+        ),
+        Some(value_ty) => (quote::quote!(#call #propagate), value_ty.clone()),
+        None => (quote::quote!(#call #propagate), syn::parse_quote!(())),
+    };
+
+    let (method_args, _) = filter_args(&method.attrs);
+    let c_result_fn = if method_args.c_by_value {
+        quote::quote!(#[::crops::c_result_fn(by_value)])
+    } else {
+        quote::quote!(#[::crops::c_result_fn])
+    };
+
+    let attrs = method
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"));
+
+    quote::quote!(
+        #(#attrs)*
+        #c_result_fn
+        fn #fn_ident(#(#params),*) -> Result<#value_ty, #error> {
+            #(#conversions)*
+            let value = #value;
+
+            Ok(value)
+        }
+    )
+}
+
+pub(crate) fn c_impl(mut item: ItemImpl) -> TokenStream {
+    if item.trait_.is_some() {
+        panic!("c_impl is only supported on inherent impl blocks");
+    }
+    if !item.generics.params.is_empty() {
+        panic!("c_impl is not supported on generic impl blocks");
+    }
+
+    let self_ty = item.self_ty.as_ref();
+    let type_name = get_wrapper_ty_ident(self_ty)
+        .to_string()
+        .to_case(Case::Snake);
+
+    let methods = item
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Fn(method) if matches!(method.vis, Visibility::Public(_)) => Some(method),
+            _ => None,
+        })
+        .map(|method| method_api(self_ty, &type_name, method))
+        .collect::<Vec<_>>();
+
+    // `#[c_builder(..)]` on methods and their arguments is only read by this macro
+    for method in item.items.iter_mut() {
+        if let ImplItem::Fn(method) = method {
+            method
+                .attrs
+                .retain(|attr| !attr.path().is_ident("c_builder"));
+            for input in method.sig.inputs.iter_mut() {
+                if let FnArg::Typed(typed) = input {
+                    typed
+                        .attrs
+                        .retain(|attr| !attr.path().is_ident("c_builder"));
+                }
+            }
+        }
+    }
+
+    quote::quote!(
+        #item
+
+        #(#methods)*
+    )
+    .into()
+}
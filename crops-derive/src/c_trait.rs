@@ -0,0 +1,174 @@
+use proc_macro::TokenStream;
+use quote::format_ident;
+use syn::{FnArg, ItemTrait, TraitItem};
+
+pub(crate) fn c_trait(item: ItemTrait, thread_safe: bool) -> TokenStream {
+    let ItemTrait {
+        vis,
+        ident,
+        items,
+        supertraits,
+        ..
+    } = &item;
+
+    let bounded = supertraits.iter().any(|bound| {
+        matches!(bound, syn::TypeParamBound::Trait(bound)
+            if bound.path.is_ident("Send") || bound.path.is_ident("Sync"))
+    });
+    if bounded && !thread_safe {
+        panic!("`{ident}` requires Send or Sync, so must be marked #[c_trait(thread_safe)]");
+    }
+
+    let vtable_ident = format_ident!("{ident}VTable");
+    let adapter_ident = format_ident!("{ident}Adapter");
+
+    let methods = items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Fn(method) => Some(method),
+            _ => None,
+        })
+        .map(|method| {
+            let mut sig = method.sig.clone();
+            if !sig.generics.params.is_empty() {
+                panic!("Generic methods are not supported by c_trait");
+            }
+            let call = match sig.inputs.first() {
+                Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => {
+                    // Only `&self` methods may be re-entered by the C implementation
+                    if receiver.mutability.is_some() {
+                        format_ident!("call_exclusive")
+                    } else {
+                        format_ident!("call")
+                    }
+                }
+                _ => panic!("c_trait methods must take &self or &mut self"),
+            };
+
+            let args = (1..sig.inputs.len())
+                .map(|idx| format_ident!("arg_{idx}"))
+                .collect::<Vec<_>>();
+            let tys = sig
+                .inputs
+                .iter_mut()
+                .skip(1)
+                .zip(&args)
+                .map(|(input, arg)| match input {
+                    FnArg::Typed(typed) => {
+                        *typed.pat = syn::parse_quote!(#arg);
+                        typed.ty.as_ref().clone()
+                    }
+                    FnArg::Receiver(_) => unreachable!(),
+                })
+                .collect::<Vec<_>>();
+
+            let name = &sig.ident;
+            let output = &sig.output;
+
+            let function = quote::quote!(extern "C" fn(*mut ::std::ffi::c_void #(, #tys)*) #output);
+            let field = quote::quote!(pub #name: Option<#function>);
+            let adapter_field = quote::quote!(#name: #function);
+            let check = quote::quote!(
+                #name: vtable.#name.ok_or_else(|| {
+                    ::crops::error::Error::null_pointer()
+                        .context(concat!(stringify!(#vtable_ident), "::", stringify!(#name)))
+                })?
+            );
+            let implementation = quote::quote!(
+                #sig {
+                    self.user_data.#call(|user_data| (self.#name)(user_data #(, #args)*))
+                }
+            );
+
+            (field, adapter_field, check, implementation)
+        })
+        .collect::<Vec<_>>();
+
+    let fields = methods.iter().map(|method| &method.0);
+    let adapter_fields = methods.iter().map(|method| &method.1);
+    let checks = methods.iter().map(|method| &method.2);
+    let implementations = methods.iter().map(|method| &method.3);
+
+    let thread_safe_impls = thread_safe.then(|| {
+        quote::quote!(
+            // Safety: promised by C with `#[c_trait(thread_safe)]`
+            unsafe impl Send for #adapter_ident {}
+            unsafe impl Sync for #adapter_ident {}
+        )
+    });
+
+    let vtable_doc = format!(
+        " Implements `{ident}` from C, every function is passed `user_data` before its own arguments."
+    );
+    let adapter_doc = format!(
+        " Implements `{ident}` by calling through a `{vtable_ident}`, whose functions are checked to be non-null"
+    );
+
+    quote::quote!(
+        #item
+
+        /// ------
+        #[doc = #vtable_doc]
+        ///
+        /// `destructor`, if provided, releases `user_data` once Rust no longer needs it.
+        /// ------
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        #vis struct #vtable_ident {
+            pub user_data: *mut ::std::ffi::c_void,
+            #(#fields,)*
+            pub destructor: ::crops::callback::Destructor,
+        }
+
+        #[doc = #adapter_doc]
+        #vis struct #adapter_ident {
+            user_data: ::crops::callback::UserData,
+            #(#adapter_fields,)*
+        }
+
+        #thread_safe_impls
+
+        /// Fails if any function is null, in which case `user_data` is released right away
+        impl TryFrom<#vtable_ident> for #adapter_ident {
+            type Error = ::crops::error::Error;
+
+            fn try_from(vtable: #vtable_ident) -> Result<Self, Self::Error> {
+                let user_data = ::crops::callback::UserData::new(vtable.user_data, vtable.destructor);
+
+                Ok(Self {
+                    #(#checks,)*
+                    user_data,
+                })
+            }
+        }
+
+        impl #ident for #adapter_ident {
+            #(#implementations)*
+        }
+
+        impl TryFrom<#vtable_ident> for Box<dyn #ident> {
+            type Error = ::crops::error::Error;
+
+            fn try_from(vtable: #vtable_ident) -> Result<Self, Self::Error> {
+                Ok(Box::new(#adapter_ident::try_from(vtable)?))
+            }
+        }
+
+        impl TryFrom<#vtable_ident> for ::std::rc::Rc<dyn #ident> {
+            type Error = ::crops::error::Error;
+
+            fn try_from(vtable: #vtable_ident) -> Result<Self, Self::Error> {
+                Ok(::std::rc::Rc::new(#adapter_ident::try_from(vtable)?))
+            }
+        }
+
+        impl TryFrom<#vtable_ident> for ::std::sync::Arc<dyn #ident> {
+            type Error = ::crops::error::Error;
+
+            fn try_from(vtable: #vtable_ident) -> Result<Self, Self::Error> {
+                Ok(::std::sync::Arc::new(#adapter_ident::try_from(vtable)?))
+            }
+        }
+    )
+    .into()
+}
@@ -5,21 +5,39 @@ use itertools::Itertools;
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, ToTokens};
+use syn::spanned::Spanned;
 use syn::{
     parse::{Parse, ParseStream},
     parse_macro_input, parse_quote,
     punctuated::Punctuated,
+    visit_mut::{self, VisitMut},
     Attribute, Data, DataEnum, DataStruct, DeriveInput, Expr, Field, Fields, GenericArgument,
-    Ident, Meta, PathArguments, PathSegment, Type, Variant, Visibility,
+    Generics, Ident, Member, Meta, PathArguments, PathSegment, Type, Variant, Visibility,
 };
 
+mod c_error;
+mod c_impl;
+mod c_trait;
+
 const COPYABLE: [&str; 13] = [
-    "i8", "i16", "i32", "i64", "i128", "u8", "u16", "u32", "u64", "u128", "f32", "f64", "bool",
+    "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize", "f32", "f64", "bool",
 ];
 
 #[proc_macro_attribute]
-pub fn c_result_fn(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn c_result_fn(attr: TokenStream, item: TokenStream) -> TokenStream {
     let input = parse_macro_input!(item as syn::ItemFn);
+    let mut out = false;
+    let mut by_value = false;
+    let attr_parser = Punctuated::<Ident, syn::Token![,]>::parse_terminated;
+    for ident in parse_macro_input!(attr with attr_parser) {
+        if ident == "out" {
+            out = true;
+        } else if ident == "by_value" {
+            by_value = true;
+        } else {
+            panic!("Unknown c_result_fn argument `{ident}`, expected `out` or `by_value`");
+        }
+    }
     let function_name = input.sig.ident.clone();
 
     let mut inner = input.clone();
@@ -32,28 +50,203 @@ pub fn c_result_fn(_attr: TokenStream, item: TokenStream) -> TokenStream {
     new_signature.abi = parse_quote!(extern "C");
     new_signature.output = parse_quote!(-> i32);
 
+    // `#[c_builder(..)]` on arguments is only read by this macro
+    let arg_args = inner
+        .sig
+        .inputs
+        .iter_mut()
+        .map(|input| match input {
+            syn::FnArg::Typed(typed) => {
+                let (args, _) = filter_args(&typed.attrs);
+                typed
+                    .attrs
+                    .retain(|attr| !attr.path().is_ident("c_builder"));
+                args
+            }
+            syn::FnArg::Receiver(_) => CBuilderFieldArgs::default(),
+        })
+        .collect_vec();
+
+    // Arguments which C can't pass directly are received as several C parameters, and converted
+    // before the inner function is called
+    let mut conversions = vec![];
+    let mut checks = vec![];
+    let params = inner.sig.inputs.iter().zip(&arg_args).map(|(input, args)| {
+        let syn::FnArg::Typed(typed) = input else {
+            return input.to_token_stream();
+        };
+        let syn::Pat::Ident(pat) = typed.pat.as_ref() else {
+            return input.to_token_stream();
+        };
+        let name = &pat.ident;
+
+        match arg_params(name, &typed.ty, args) {
+            Some((params, expr, borrow)) => {
+                conversions.push(quote::quote!(
+                    let #name = match #expr {
+                        Ok(value) => value,
+                        Err(e) => return ::crops::error::into_code(Err::<(), _>(e)),
+                    };
+                    #borrow
+                ));
+                params
+            }
+            None => {
+                if matches!(typed.ty.as_ref(), Type::Path(_)) {
+                    checks.push(int_enum_check(
+                        [typed.ty.as_ref()],
+                        &format!("Argument `{name}` of {function_name} is a C enum, so must be marked #[c_builder(c_int_enum)]"),
+                    ));
+                }
+                input.to_token_stream()
+            }
+        }
+    });
+    let mut params = params.collect_vec();
+
+    // The `Ok` value, a value of a known type, or a value returned directly by an `out` function,
+    // is handed to C through a trailing `out`
+    let by_copy = |ty: &Type| match ty {
+        Type::Path(path) => path.path.get_ident().is_some_and(|ident| {
+            COPYABLE.contains(&ident.to_string().as_str())
+                || ["String", "char", "i128", "u128"].contains(&ident.to_string().as_str())
+        }),
+        _ => false,
+    };
+    let (fallible, value_ty) = match &input.sig.output {
+        syn::ReturnType::Default => (false, None),
+        syn::ReturnType::Type(_, ty) if out => (false, Some(ty.as_ref().clone())),
+        syn::ReturnType::Type(_, ty) => match ty.as_ref() {
+            Type::Path(path) if path.path.segments.last().unwrap().ident == "Result" => (
+                true,
+                parse_angle_bracket(path.path.segments.last().unwrap()).cloned(),
+            ),
+            Type::Path(path) if path.path.segments.last().unwrap().ident == "CResult" => {
+                (true, None)
+            }
+            Type::Tuple(tuple) if tuple.elems.is_empty() => (false, None),
+            ty if by_copy(ty) => (false, Some(ty.clone())),
+            // Any other plain path must be a CBuilder type, rather than e.g. an alias of `Result`
+            Type::Path(path)
+                if path.qself.is_none()
+                    && path
+                        .path
+                        .segments
+                        .iter()
+                        .all(|segment| segment.arguments.is_none()) =>
+            {
+                let message = format!(
+                    "{function_name} returns `{}`, which is neither a Result nor a CBuilder type, so must spell out `Result<T, E>` or use #[c_result_fn(out)]",
+                    ty.to_token_stream()
+                );
+                checks.push(quote::quote! {
+                    const _: () = {
+                        use ::crops::traits::MaybeByValue as _;
+                        assert!(<#ty>::CROPS_BUILDER, #message);
+                    };
+                });
+                (false, Some(ty.as_ref().clone()))
+            }
+            ty => {
+                return syn::Error::new_spanned(
+                    ty,
+                    "Can't tell how to return this type to C, spell out `Result<T, E>`, or use #[c_result_fn(out)] to return it as a value",
+                )
+                .to_compile_error()
+                .into()
+            }
+        },
+    };
+    let value_ty =
+        value_ty.filter(|ty| !matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty()));
+
+    let write = value_ty.map(|ty| {
+        let out = format_ident!("out");
+
+        if by_copy(&ty) {
+            let CTypes {
+                to_c,
+                to_c_extra,
+                to_c_parser,
+                ..
+            } = gen_c_types_inner(&Default::default(), &ty);
+            let unparser = to_c_parser(&out, &format_ident!("value"));
+
+            params.push(quote::quote!(#out: #to_c #to_c_extra));
+            quote::quote!(
+                let value = &value;
+                #unparser;
+            )
+        } else if by_value {
+            // Types passed by value are written to a `T *` that C provides, like copyable ones
+            params.push(quote::quote!(#out: *mut #ty));
+            quote::quote!(
+                *::crops::utils::check_null(#out)? = ::crops::traits::by_value(value);
+            )
+        } else {
+            // Anything else is opaque to C, so is boxed, and must be freed by the caller
+            let message = format!(
+                "{function_name} returns a type passed by value, so must be marked #[c_result_fn(by_value)]"
+            );
+            checks.push(quote::quote! {
+                const _: () = {
+                    use ::crops::traits::MaybeByValue as _;
+                    assert!(!<#ty>::CROPS_BY_VALUE, #message);
+                };
+            });
+
+            params.push(quote::quote!(#out: *mut *mut #ty));
+            quote::quote!(
+                *::crops::utils::check_null(#out)? = Box::into_raw(Box::new(value));
+            )
+        }
+    });
+    new_signature.inputs = parse_quote!(#(#params),*);
+
     let inputs = inner.sig.inputs.iter().map(|input| match input {
         syn::FnArg::Receiver(_) => panic!("Self argument is not supported in ABI"),
-        syn::FnArg::Typed(typed) => {
-            let pat = typed.pat.as_ref();
-            pat
-        }
+        syn::FnArg::Typed(typed) => match typed.pat.as_ref() {
+            // Binding modes such as `mut` only apply within the inner function
+            syn::Pat::Ident(pat) => pat.ident.to_token_stream(),
+            pat => pat.to_token_stream(),
+        },
     });
 
+    let call = match (fallible, write) {
+        (true, None) => quote::quote!(::crops::error::into_code(#inner_ident(#(#inputs),*))),
+        (true, Some(write)) => quote::quote!(match #inner_ident(#(#inputs),*) {
+            Ok(value) => ::crops::error::into_code((|| -> ::crops::utils::CResult {
+                #write
+                Ok(())
+            })()),
+            Err(e) => ::crops::error::into_code(Err::<(), _>(e)),
+        }),
+        (false, None) => quote::quote!(
+            #inner_ident(#(#inputs),*);
+            ::crops::error::CropsErrorCode::Ok as i32
+        ),
+        (false, Some(write)) => quote::quote!(
+            let value = #inner_ident(#(#inputs),*);
+            ::crops::error::into_code((|| -> ::crops::utils::CResult {
+                #write
+                Ok(())
+            })())
+        ),
+    };
+
     let args = input.attrs;
     quote::quote!(
         #inner
 
+        #(#checks)*
+
         #(#args)*
         #[no_mangle]
         pub #new_signature {
-            match #inner_ident(#(#inputs),*) {
-                Ok(_) => 0,
-                Err(e) => {
-                    eprintln!("{:?}", e);
-                    1
-                }
-            }
+            ::crops::error::catch_panic(::crops::error::CropsErrorCode::Panic as i32, || {
+                #(#conversions)*
+                #call
+            })
         }
     )
     .into()
@@ -62,13 +255,183 @@ pub fn c_result_fn(_attr: TokenStream, item: TokenStream) -> TokenStream {
 #[proc_macro_derive(CBuilder, attributes(c_builder))]
 pub fn derive_d_builder(item: TokenStream) -> TokenStream {
     let DeriveInput {
-        attrs, ident, data, ..
+        attrs,
+        vis,
+        ident,
+        generics,
+        data,
     } = parse_macro_input!(item as DeriveInput);
 
-    match data {
-        Data::Enum(e) => derive_c_builder_enum(ident, attrs, e),
-        Data::Struct(s) => derive_c_builder_struct(ident, attrs, s),
+    if generics.params.is_empty() {
+        return derive_c_builder_data(ident, vis, attrs, data);
+    }
+
+    let mut args = CBuilderArgs::default();
+    args.extract(attrs.clone());
+
+    if args.c_instantiations.is_empty() {
+        panic!("Generic types must be instantiated with #[c_builder(instantiate({ident}<..> = Alias))]");
+    }
+
+    args.c_instantiations
+        .iter()
+        .map(|Instantiation { ty, alias }| -> TokenStream2 {
+            let data = instantiate_data(&ident, &generics, ty, data.clone());
+            let api = TokenStream2::from(derive_c_builder_data(
+                alias.clone(),
+                vis.clone(),
+                attrs.clone(),
+                data,
+            ));
+
+            quote::quote!(
+                #vis type #alias = #ty;
+
+                #api
+            )
+        })
+        .collect::<TokenStream2>()
+        .into()
+}
+
+fn derive_c_builder_data(
+    ident: Ident,
+    vis: Visibility,
+    attrs: Vec<Attribute>,
+    data: Data,
+) -> TokenStream {
+    let api = TokenStream2::from(match data {
+        Data::Enum(e) => derive_c_builder_enum(ident.clone(), vis, attrs, e),
+        Data::Struct(s) => derive_c_builder_struct(ident.clone(), attrs, s),
+        Data::Union(_) => panic!("Not supported for unions"),
+    });
+
+    quote::quote!(
+        impl #ident {
+            #[doc(hidden)]
+            pub const CROPS_BUILDER: bool = true;
+        }
+
+        #api
+    )
+    .into()
+}
+
+/// A concrete instantiation of a generic type, exported under `alias`
+struct Instantiation {
+    ty: Type,
+    alias: Ident,
+}
+
+impl Parse for Instantiation {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ty = input.parse()?;
+        input.parse::<syn::Token![=]>()?;
+        let alias = input.parse()?;
+
+        Ok(Self { ty, alias })
+    }
+}
+
+/// Replaces the generic parameters of a type with the types they are instantiated with
+struct Substitute(HashMap<Ident, Type>);
+
+impl VisitMut for Substitute {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(path) = ty {
+            if let Some(concrete) = path
+                .path
+                .get_ident()
+                .filter(|_| path.qself.is_none())
+                .and_then(|ident| self.0.get(ident))
+            {
+                *ty = concrete.clone();
+                return;
+            }
+        }
+
+        visit_mut::visit_type_mut(self, ty);
+    }
+}
+
+fn instantiate_data(ident: &Ident, generics: &Generics, ty: &Type, mut data: Data) -> Data {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last().unwrap(),
+        _ => panic!("Instantiations must be of the form {ident}<..>"),
+    };
+
+    if &segment.ident != ident {
+        panic!("Instantiations must be of the form {ident}<..>");
+    }
+
+    let concrete = match &segment.arguments {
+        PathArguments::AngleBracketed(angle) => angle
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(t) => Some(t.clone()),
+                _ => None,
+            })
+            .collect_vec(),
+        _ => vec![],
+    };
+
+    let params = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect_vec();
+
+    if params.len() != concrete.len() {
+        panic!(
+            "Instantiations of {ident} must provide {} types",
+            params.len()
+        );
+    }
+
+    let mut substitute = Substitute(params.into_iter().zip(concrete).collect());
+
+    let fields = match &mut data {
+        Data::Struct(s) => s.fields.iter_mut().collect_vec(),
+        Data::Enum(e) => e
+            .variants
+            .iter_mut()
+            .flat_map(|variant| variant.fields.iter_mut())
+            .collect_vec(),
         Data::Union(_) => panic!("Not supported for unions"),
+    };
+
+    for field in fields {
+        substitute.visit_type_mut(&mut field.ty);
+    }
+
+    data
+}
+
+#[proc_macro_attribute]
+pub fn c_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    c_impl::c_impl(parse_macro_input!(item as syn::ItemImpl))
+}
+
+#[proc_macro_attribute]
+pub fn c_trait(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let thread_safe = match parse_macro_input!(attr as Option<Ident>) {
+        Some(attr) if attr == "thread_safe" => true,
+        Some(attr) => panic!("Unknown c_trait attribute `{attr}`, expected `thread_safe`"),
+        None => false,
+    };
+
+    c_trait::c_trait(parse_macro_input!(item as syn::ItemTrait), thread_safe)
+}
+
+#[proc_macro_derive(CError, attributes(c_error))]
+pub fn derive_c_error(item: TokenStream) -> TokenStream {
+    let DeriveInput {
+        ident, vis, data, ..
+    } = parse_macro_input!(item as DeriveInput);
+
+    match data {
+        Data::Enum(e) => c_error::derive_c_error(ident, vis, e),
+        _ => panic!("CError is only supported for enums"),
     }
 }
 
@@ -78,6 +441,13 @@ struct CBuilderFieldArgs {
     c_as: Option<syn::Type>,
     c_input: Option<syn::Expr>,
     c_parser: Option<proc_macro2::TokenStream>,
+    c_owned: bool,
+    c_bytes: bool,
+    c_by_value: bool,
+    c_int_enum: bool,
+    c_thread_safe: bool,
+    c_take: bool,
+    c_name: Option<Ident>,
 }
 
 impl Parse for CBuilderFieldArgs {
@@ -89,6 +459,18 @@ impl Parse for CBuilderFieldArgs {
             if let syn::Expr::Path(p) = expr {
                 if p.path.is_ident("c_enum") {
                     res.c_enum = true;
+                } else if p.path.is_ident("c_owned") {
+                    res.c_owned = true;
+                } else if p.path.is_ident("c_bytes") {
+                    res.c_bytes = true;
+                } else if p.path.is_ident("c_by_value") {
+                    res.c_by_value = true;
+                } else if p.path.is_ident("c_int_enum") {
+                    res.c_int_enum = true;
+                } else if p.path.is_ident("c_thread_safe") {
+                    res.c_thread_safe = true;
+                } else if p.path.is_ident("c_take") {
+                    res.c_take = true;
                 }
             } else if let syn::Expr::Assign(assign) = expr {
                 if let syn::Expr::Path(p) = assign.left.as_ref() {
@@ -99,6 +481,9 @@ impl Parse for CBuilderFieldArgs {
                         res.c_input.replace(assign.right.as_ref().clone());
                     } else if p.path.is_ident("c_parser") {
                         res.c_parser.replace(assign.right.to_token_stream());
+                    } else if p.path.is_ident("c_name") {
+                        res.c_name
+                            .replace(parse_ident(assign.right.as_ref()).clone());
                     }
                 }
             }
@@ -108,13 +493,16 @@ impl Parse for CBuilderFieldArgs {
     }
 }
 
-#[derive(Debug)]
 struct CBuilderArgs {
     c_new: bool,
     c_clone: bool,
     c_debug: bool,
     // TODO(bjc) permit extra constructors
     c_constructors: Vec<Vec<syn::Ident>>,
+    c_transparent: bool,
+    c_pod: bool,
+    c_int_enum: bool,
+    c_instantiations: Vec<Instantiation>,
 }
 
 impl Default for CBuilderArgs {
@@ -124,6 +512,10 @@ impl Default for CBuilderArgs {
             c_clone: true,
             c_debug: true,
             c_constructors: Default::default(),
+            c_transparent: false,
+            c_pod: false,
+            c_int_enum: false,
+            c_instantiations: Default::default(),
         }
     }
 }
@@ -138,6 +530,10 @@ impl CBuilderArgs {
                     self.c_new = line.c_new;
                     self.c_debug = line.c_debug;
                     self.c_constructors.extend(line.c_constructors);
+                    self.c_transparent |= line.c_transparent;
+                    self.c_pod |= line.c_pod;
+                    self.c_int_enum |= line.c_int_enum;
+                    self.c_instantiations.extend(line.c_instantiations);
                     return false;
                 }
             }
@@ -165,10 +561,41 @@ macro_rules! get_literal {
 impl Parse for CBuilderArgs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut res = Self::default();
-        let result = Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated(input)?;
+        let mut result = vec![];
+
+        // `instantiate(..)` holds types rather than expressions, so is parsed separately
+        while !input.is_empty() {
+            let fork = input.fork();
+            if fork
+                .parse::<Ident>()
+                .is_ok_and(|ident| ident == "instantiate")
+                && fork.peek(syn::token::Paren)
+            {
+                input.parse::<Ident>()?;
+                let content;
+                syn::parenthesized!(content in input);
+                res.c_instantiations.extend(
+                    Punctuated::<Instantiation, syn::Token![,]>::parse_terminated(&content)?,
+                );
+            } else {
+                result.push(input.parse::<syn::Expr>()?);
+            }
+
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
 
         for expr in result {
-            if let syn::Expr::Assign(assign) = expr {
+            if let syn::Expr::Path(p) = expr {
+                if p.path.is_ident("transparent") {
+                    res.c_transparent = true;
+                } else if p.path.is_ident("pod") {
+                    res.c_pod = true;
+                } else if p.path.is_ident("int_enum") {
+                    res.c_int_enum = true;
+                }
+            } else if let syn::Expr::Assign(assign) = expr {
                 if let syn::Expr::Path(p) = assign.left.as_ref() {
                     if p.path.is_ident("new") {
                         res.c_new = get_literal!(assign.right.as_ref(), Bool);
@@ -203,6 +630,300 @@ fn parse_angle_bracket(segment: &PathSegment) -> Option<&Type> {
     None
 }
 
+/// What C implements for a foreign value, see `ForeignTy`
+enum ForeignKind {
+    /// A `dyn Fn(..)`, received as a function pointer
+    Callback {
+        inputs: Vec<Type>,
+        output: syn::ReturnType,
+        /// A `dyn FnMut(..)`, which must not be re-entered
+        mutable: bool,
+    },
+    /// A `dyn Trait` of a `#[c_trait]`, received as a pointer to its vtable
+    TraitObject {
+        vtable: syn::Path,
+        adapter: syn::Path,
+    },
+}
+
+/// A `Box`, `Rc` or `Arc` of a `dyn Fn(..)` or `#[c_trait]` trait, optionally within an `Option`
+struct ForeignTy {
+    ty: Type,
+    optional: bool,
+    pointer: Type,
+    constructor: syn::Path,
+    kind: ForeignKind,
+    /// Bounded by `Send` or `Sync`
+    thread_safe: bool,
+}
+
+fn foreign_ty(ty: &Type) -> Option<ForeignTy> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let last = path.path.segments.last().unwrap();
+
+    if last.ident == "Option" {
+        return foreign_ty(parse_angle_bracket(last)?).map(|foreign| ForeignTy {
+            ty: ty.clone(),
+            optional: true,
+            ..foreign
+        });
+    }
+
+    if !matches!(last.ident.to_string().as_str(), "Box" | "Rc" | "Arc") {
+        return None;
+    }
+    let Some(Type::TraitObject(object)) = parse_angle_bracket(last) else {
+        return None;
+    };
+
+    // Marker traits such as `Send` are skipped over, the first trait is the one C implements
+    let kind = object.bounds.iter().find_map(|bound| match bound {
+        syn::TypeParamBound::Trait(bound) => {
+            let segment = bound.path.segments.last().unwrap();
+            match &segment.arguments {
+                PathArguments::Parenthesized(args)
+                    if segment.ident == "Fn" || segment.ident == "FnMut" =>
+                {
+                    Some(ForeignKind::Callback {
+                        inputs: args.inputs.iter().cloned().collect(),
+                        output: args.output.clone(),
+                        mutable: segment.ident == "FnMut",
+                    })
+                }
+                PathArguments::None
+                    if !["Send", "Sync"].contains(&segment.ident.to_string().as_str()) =>
+                {
+                    let with_suffix = |suffix: &str| {
+                        let mut path = bound.path.clone();
+                        let segment = path.segments.last_mut().unwrap();
+                        segment.ident = format_ident!("{}{suffix}", segment.ident);
+                        path
+                    };
+
+                    Some(ForeignKind::TraitObject {
+                        vtable: with_suffix("VTable"),
+                        adapter: with_suffix("Adapter"),
+                    })
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    })?;
+    let thread_safe = object.bounds.iter().any(|bound| {
+        matches!(bound, syn::TypeParamBound::Trait(bound)
+            if bound.path.is_ident("Send") || bound.path.is_ident("Sync"))
+    });
+
+    let mut constructor = path.path.clone();
+    constructor.segments.last_mut().unwrap().arguments = PathArguments::None;
+
+    Some(ForeignTy {
+        ty: ty.clone(),
+        optional: false,
+        pointer: ty.clone(),
+        constructor,
+        kind,
+        thread_safe,
+    })
+}
+
+/// The C parameters a foreign value named `name` is received as, and the expression converting
+/// them into the Rust value, as a `Result` which is an error if a required value is null.
+///
+/// `thread_safe` is set by `c_thread_safe`, C's promise that a `Send` or `Sync` callback is safe
+/// to call from any thread.
+fn foreign_params(
+    name: &Ident,
+    foreign: &ForeignTy,
+    thread_safe: bool,
+) -> (TokenStream2, TokenStream2) {
+    let ForeignTy {
+        ty,
+        optional,
+        pointer,
+        constructor,
+        kind,
+        ..
+    } = foreign;
+
+    let (params, wrapped) = match kind {
+        ForeignKind::Callback {
+            inputs,
+            output,
+            mutable,
+        } => {
+            if foreign.thread_safe && !thread_safe {
+                panic!(
+                    "`{}` is Send or Sync, so must be marked #[c_builder(c_thread_safe)]",
+                    ty.to_token_stream()
+                );
+            }
+            let user_data = format_ident!("{name}_user_data");
+            let destructor = format_ident!("{name}_destructor");
+            let args = (0..inputs.len())
+                .map(|idx| format_ident!("arg_{idx}"))
+                .collect_vec();
+            let call = if *mutable {
+                format_ident!("call_exclusive")
+            } else {
+                format_ident!("call")
+            };
+            let wrap = thread_safe.then(|| {
+                quote::quote!(
+                    // Safety: promised by C with `c_thread_safe`
+                    let user_data = unsafe { ::crops::callback::ThreadSafe::new(user_data) };
+                )
+            });
+
+            (
+                quote::quote!(
+                    #name: Option<extern "C" fn(*mut ::std::ffi::c_void #(, #inputs)*) #output>,
+                    #user_data: *mut ::std::ffi::c_void,
+                    #destructor: ::crops::callback::Destructor
+                ),
+                quote::quote!({
+                    let user_data = ::crops::callback::UserData::new(#user_data, #destructor);
+                    #wrap
+                    Ok::<_, ::crops::error::Error>(#name.map(move |callback| -> #pointer {
+                        #constructor::new(move |#(#args: #inputs),*| {
+                            user_data.#call(|user_data| callback(user_data #(, #args)*))
+                        })
+                    }))
+                }),
+            )
+        }
+        // The adapter checks the vtable, and is then coerced into the trait object along with any
+        // marker traits such as `Send`
+        ForeignKind::TraitObject { vtable, adapter } => (
+            quote::quote!(#name: *const #vtable),
+            quote::quote!(::crops::utils::check_null_const(#name)
+                .ok()
+                .map(|vtable| {
+                    #adapter::try_from(*vtable).map(|adapter| -> #pointer { #constructor::new(adapter) })
+                })
+                .transpose()),
+        ),
+    };
+
+    let expr = if *optional {
+        quote::quote!(#wrapped.map(|value| -> #ty { value }))
+    } else {
+        quote::quote!(#wrapped.and_then(|value| value.ok_or_else(|| {
+            ::crops::error::Error::null_pointer().context(stringify!(#name))
+        })))
+    };
+
+    (params, expr)
+}
+
+/// The C parameters an argument named `name` of a `c_result_fn` is received as, if it needs
+/// converting, the expression checking and converting them as a `Result`, and an optional rebinding
+/// of the converted value, e.g. to borrow a `&str` from the converted `String`
+fn arg_params(
+    name: &Ident,
+    ty: &Type,
+    args: &CBuilderFieldArgs,
+) -> Option<(TokenStream2, TokenStream2, Option<TokenStream2>)> {
+    let context = quote::quote!(.map_err(|e| e.context(stringify!(#name))));
+
+    if args.c_int_enum {
+        return Some((
+            quote::quote!(#name: i32),
+            quote::quote!(<#ty as ::crops::traits::IntEnum>::from_int(#name) #context),
+            None,
+        ));
+    }
+
+    if let Some(foreign) = foreign_ty(ty) {
+        let (params, expr) = foreign_params(name, &foreign, args.c_thread_safe);
+        return Some((params, expr, None));
+    }
+
+    let is_str = |ty: &Type| matches!(ty, Type::Path(path) if path.path.is_ident("str"));
+
+    match ty {
+        Type::Path(path) if path.path.is_ident("String") => Some((
+            quote::quote!(#name: *const ::crops::_macros::libc::c_char),
+            quote::quote!(::crops::utils::as_string(#name) #context),
+            None,
+        )),
+        Type::Path(path) if path.path.segments.last().unwrap().ident == "Option" => {
+            match parse_angle_bracket(path.path.segments.last().unwrap()) {
+                Some(Type::Reference(reference)) if reference.mutability.is_none() => {
+                    let elem = reference.elem.as_ref();
+                    if is_str(elem) {
+                        Some((
+                            quote::quote!(#name: *const ::crops::_macros::libc::c_char),
+                            quote::quote!((!#name.is_null())
+                                .then(|| ::crops::utils::as_string(#name))
+                                .transpose()
+                                #context),
+                            Some(quote::quote!(let #name = #name.as_deref();)),
+                        ))
+                    } else {
+                        Some((
+                            quote::quote!(#name: *const #elem),
+                            quote::quote!(Ok::<_, ::crops::error::Error>(
+                                ::crops::utils::check_null_const(#name).ok()
+                            )),
+                            None,
+                        ))
+                    }
+                }
+                _ => None,
+            }
+        }
+        Type::Reference(reference) => {
+            let elem = reference.elem.as_ref();
+            match (elem, reference.mutability) {
+                (elem, None) if is_str(elem) => Some((
+                    quote::quote!(#name: *const ::crops::_macros::libc::c_char),
+                    quote::quote!(::crops::utils::as_string(#name) #context),
+                    Some(quote::quote!(let #name = #name.as_str();)),
+                )),
+                (Type::Slice(slice), None) => {
+                    let elem = slice.elem.as_ref();
+                    let len = format_ident!("{name}_len");
+                    Some((
+                        quote::quote!(#name: *const #elem, #len: usize),
+                        quote::quote!(::crops::utils::as_slice(#name, #len) #context),
+                        None,
+                    ))
+                }
+                (Type::Slice(_), Some(_)) => None,
+                (elem, None) => Some((
+                    quote::quote!(#name: *const #elem),
+                    quote::quote!(::crops::utils::check_null_const(#name) #context),
+                    None,
+                )),
+                (elem, Some(_)) => Some((
+                    quote::quote!(#name: *mut #elem),
+                    quote::quote!(::crops::utils::check_null(#name) #context),
+                    None,
+                )),
+            }
+        }
+        _ => None,
+    }
+}
+
+fn parse_angle_brackets(segment: &PathSegment) -> Vec<&Type> {
+    if let PathArguments::AngleBracketed(angle) = &segment.arguments {
+        return angle
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                GenericArgument::Type(t) => Some(t),
+                _ => None,
+            })
+            .collect();
+    }
+    vec![]
+}
+
 fn parse_ident(expr: &Expr) -> &Ident {
     match expr {
         Expr::Path(p) => p.path.get_ident().unwrap(),
@@ -223,6 +944,13 @@ fn filter_args(attrs: &[Attribute]) -> (CBuilderFieldArgs, Vec<&Attribute>) {
                         mine.c_as = line.c_as;
                         mine.c_input = line.c_input;
                         mine.c_parser = line.c_parser;
+                        mine.c_owned = line.c_owned;
+                        mine.c_bytes = line.c_bytes;
+                        mine.c_by_value = line.c_by_value;
+                        mine.c_int_enum = line.c_int_enum;
+                        mine.c_thread_safe = line.c_thread_safe;
+                        mine.c_take = line.c_take;
+                        mine.c_name = line.c_name;
                     } else {
                         others.push(attr)
                     }
@@ -239,14 +967,36 @@ fn filter_args(attrs: &[Attribute]) -> (CBuilderFieldArgs, Vec<&Attribute>) {
 }
 
 fn consider_copyable(args: &CBuilderFieldArgs, ty: &Type) -> CTypes {
+    // C may pass any integer, so it is checked before becoming an enum
+    if args.c_int_enum {
+        return CTypes {
+            rust: ty.clone(),
+            from_c: parse_quote!(i32),
+            from_c_parser: Box::new(
+                |ident| parse_quote!(::crops::traits::IntEnum::from_int(#ident)?),
+            ),
+            to_c: parse_quote!(*mut #ty),
+            to_c_extra: Default::default(),
+            to_c_parser: Box::new(
+                |c_value, value| parse_quote!(*::crops::utils::check_null(#c_value)? = *#value),
+            ),
+            to_c_owned: None,
+        };
+    }
+
     if let Type::Path(path) = ty {
         if let Some(ident) = path.path.get_ident() {
-            if COPYABLE.contains(&ident.to_string().as_str()) || args.c_enum {
+            if COPYABLE.contains(&ident.to_string().as_str()) || args.c_enum || args.c_by_value {
                 return CTypes {
                     rust: ty.clone(),
                     from_c: parse_quote!(#ty),
-                    from_c_parser: Box::new(|ident| parse_quote!(#ident)),
+                    from_c_parser: if args.c_by_value {
+                        Box::new(|ident| parse_quote!(::crops::traits::by_value(#ident)))
+                    } else {
+                        Box::new(|ident| parse_quote!(#ident))
+                    },
                     to_c: parse_quote!(*mut #ty),
+                    to_c_extra: Default::default(),
                     to_c_parser: if args.c_enum {
                         Box::new(
                             |c_value, value| parse_quote!(*::crops::utils::check_null(#c_value)? = #value.clone()),
@@ -256,6 +1006,7 @@ fn consider_copyable(args: &CBuilderFieldArgs, ty: &Type) -> CTypes {
                             |c_value, value| parse_quote!(*::crops::utils::check_null(#c_value)? = *#value),
                         )
                     },
+                    to_c_owned: None,
                 };
             }
         }
@@ -266,18 +1017,28 @@ fn consider_copyable(args: &CBuilderFieldArgs, ty: &Type) -> CTypes {
         from_c: parse_quote!(&#ty),
         from_c_parser: Box::new(|ident| parse_quote!(#ident.clone())),
         to_c: parse_quote!(*mut #ty),
+        to_c_extra: Default::default(),
         to_c_parser: Box::new(
             |c_value, value| parse_quote!(*::crops::utils::check_null(#c_value)? = #value.clone()),
         ),
+        to_c_owned: None,
     }
 }
 
+type FromCParser = Box<dyn Fn(&Ident) -> TokenStream2>;
+type ToCParser = Box<dyn Fn(&Ident, &Ident) -> TokenStream2>;
+type ToCOwned = Box<dyn Fn(&Ident) -> TokenStream2>;
+
 struct CTypes {
     rust: Type,
     from_c: Type,
-    from_c_parser: Box<dyn Fn(&Ident) -> TokenStream2>,
+    from_c_parser: FromCParser,
     to_c: Type,
-    to_c_parser: Box<dyn Fn(&Ident, &Ident) -> TokenStream2>,
+    /// Extra parameters the getters need after `c_value`, e.g. a buffer capacity
+    to_c_extra: TokenStream2,
+    to_c_parser: ToCParser,
+    /// Conversion of a value into a newly allocated C value, for types that support it
+    to_c_owned: Option<ToCOwned>,
 }
 
 fn get_wrapper_ty_ident(ty: &Type) -> &Ident {
@@ -287,6 +1048,19 @@ fn get_wrapper_ty_ident(ty: &Type) -> &Ident {
     panic!()
 }
 
+fn is_byte_vec(ty: &Type) -> bool {
+    if let Type::Path(path) = ty {
+        let last = path.path.segments.last().unwrap();
+        if last.ident == "Vec" {
+            return matches!(
+                parse_angle_bracket(last),
+                Some(Type::Path(inner)) if inner.path.is_ident("u8")
+            );
+        }
+    }
+    false
+}
+
 fn gen_c_types_inner(args: &CBuilderFieldArgs, ty: &Type) -> CTypes {
     if let Type::Path(path) = &ty {
         let last = path.path.segments.last().unwrap();
@@ -298,10 +1072,46 @@ fn gen_c_types_inner(args: &CBuilderFieldArgs, ty: &Type) -> CTypes {
                 from_c: parse_quote!(&::crops::_macros::libc::c_char),
                 from_c_parser: Box::new(|ident| parse_quote!(= ::crops::utils::as_string(#ident)?)),
                 to_c: parse_quote!(*mut ::crops::_macros::libc::c_char),
+                to_c_extra: quote::quote!(, c_capacity: usize, c_length: *mut usize),
+                to_c_parser: Box::new(
+                    |c_value, value| parse_quote!(::crops::utils::copy_string(#c_value, c_capacity, c_length, #value)?),
+                ),
+                to_c_owned: Some(Box::new(
+                    |value| parse_quote!(::crops::utils::into_c_string(#value)),
+                )),
+            },
+            "char" => CTypes {
+                rust: ty.clone(),
+                from_c: parse_quote!(u32),
+                from_c_parser: Box::new(|ident| parse_quote!(= ::crops::utils::as_char(#ident)?)),
+                to_c: parse_quote!(*mut u32),
+                to_c_extra: Default::default(),
                 to_c_parser: Box::new(
-                    |c_value, value| parse_quote!(::crops::utils::copy_string(#c_value, #value)?),
+                    |c_value, value| parse_quote!(*::crops::utils::check_null(#c_value)? = u32::from(*#value)),
                 ),
+                to_c_owned: None,
             },
+            "i128" | "u128" => {
+                let split: Type = if ty_outer == "i128" {
+                    parse_quote!(::crops::utils::int128::CropsI128)
+                } else {
+                    parse_quote!(::crops::utils::int128::CropsU128)
+                };
+
+                let rust = ty.clone();
+
+                CTypes {
+                    rust: ty.clone(),
+                    from_c: split.clone(),
+                    from_c_parser: Box::new(move |ident| parse_quote!(= #rust::from(#ident))),
+                    to_c: parse_quote!(*mut #split),
+                    to_c_extra: Default::default(),
+                    to_c_parser: Box::new(
+                        |c_value, value| parse_quote!(*::crops::utils::check_null(#c_value)? = (*#value).into()),
+                    ),
+                    to_c_owned: None,
+                }
+            }
             // If we wanted to do other special cases
             // that would go here
             e => {
@@ -310,7 +1120,9 @@ fn gen_c_types_inner(args: &CBuilderFieldArgs, ty: &Type) -> CTypes {
                         from_c,
                         from_c_parser,
                         to_c,
+                        to_c_extra,
                         to_c_parser,
+                        to_c_owned,
                         ..
                     } = gen_c_types_inner(args, inner);
 
@@ -326,7 +1138,24 @@ fn gen_c_types_inner(args: &CBuilderFieldArgs, ty: &Type) -> CTypes {
                             }),
                             from_c,
                             to_c,
+                            to_c_extra,
+                            to_c_parser,
+                            to_c_owned,
+                        },
+                        "HashSet" | "BTreeSet" => CTypes {
+                            rust: ty.clone(),
+                            from_c_parser: Box::new(move |ident| {
+                                let inner = (from_c_parser)(ident);
+                                parse_quote!(.insert({
+                                    let item #inner;
+                                    item
+                                }))
+                            }),
+                            from_c,
+                            to_c,
+                            to_c_extra,
                             to_c_parser,
+                            to_c_owned,
                         },
                         "Option" => CTypes {
                             rust: ty.clone(),
@@ -339,14 +1168,18 @@ fn gen_c_types_inner(args: &CBuilderFieldArgs, ty: &Type) -> CTypes {
                             }),
                             from_c,
                             to_c,
+                            to_c_extra,
                             to_c_parser,
+                            to_c_owned,
                         },
                         _ => CTypes {
                             rust: ty.clone(),
                             from_c,
                             from_c_parser,
                             to_c,
+                            to_c_extra,
                             to_c_parser,
+                            to_c_owned,
                         },
                     }
                 } else {
@@ -355,7 +1188,9 @@ fn gen_c_types_inner(args: &CBuilderFieldArgs, ty: &Type) -> CTypes {
                         from_c,
                         from_c_parser,
                         to_c,
+                        to_c_extra,
                         to_c_parser,
+                        to_c_owned,
                     } = consider_copyable(args, ty);
                     CTypes {
                         from_c,
@@ -365,7 +1200,9 @@ fn gen_c_types_inner(args: &CBuilderFieldArgs, ty: &Type) -> CTypes {
                         }),
                         rust,
                         to_c,
+                        to_c_extra,
                         to_c_parser,
+                        to_c_owned,
                     }
                 }
             }
@@ -375,75 +1212,1339 @@ fn gen_c_types_inner(args: &CBuilderFieldArgs, ty: &Type) -> CTypes {
     }
 }
 
-fn gen_c_types(field: &Field) -> CTypes {
-    let (args, _) = filter_args(&field.attrs);
+/// Fails to compile if any of `tys` is a C enum, which C could pass any integer for
+fn int_enum_check<'a>(tys: impl IntoIterator<Item = &'a Type>, message: &str) -> TokenStream2 {
+    struct Collect(Vec<Type>);
+
+    impl VisitMut for Collect {
+        fn visit_type_mut(&mut self, ty: &mut Type) {
+            if let Type::Path(path) = ty {
+                if path.qself.is_none()
+                    && path
+                        .path
+                        .segments
+                        .iter()
+                        .all(|segment| segment.arguments.is_none())
+                {
+                    self.0.push(ty.clone());
+                }
+            }
 
-    gen_c_types_inner(&args, args.c_as.as_ref().unwrap_or(&field.ty))
+            visit_mut::visit_type_mut(self, ty);
+        }
+    }
+
+    let mut collect = Collect(vec![]);
+    for ty in tys {
+        collect.visit_type_mut(&mut ty.clone());
+    }
+    let tys = collect.0;
+
+    quote::quote! {
+        const _: () = {
+            use ::crops::traits::MaybeByValue as _;
+            #(assert!(!<#tys>::CROPS_INT_ENUM, #message);)*
+        };
+    }
 }
 
-fn generate_struct_field_api(ident: &Ident, field: &Field) -> Option<TokenStream2> {
-    let (_, filtered_attrs) = filter_args(&field.attrs);
+/// C enums within fields marked `c_by_value` would be received from C unchecked
+fn field_int_enum_check(owner: &Ident, name: &Ident, field: &Field) -> Option<TokenStream2> {
+    let (args, _) = filter_args(&field.attrs);
 
-    let field_ident = field
-        .ident
-        .as_ref()
-        .expect("All fields must be named ident fields");
+    args.c_by_value.then(|| {
+        int_enum_check(
+            [args.c_as.as_ref().unwrap_or(&field.ty)],
+            &format!("{owner}.{name} holds a C enum, so must be marked #[c_builder(c_int_enum)] rather than c_by_value"),
+        )
+    })
+}
 
-    let CTypes {
-        rust,
-        from_c,
-        from_c_parser,
-        to_c,
-        to_c_parser,
-    } = gen_c_types(field);
+fn gen_c_types(field: &Field) -> CTypes {
+    let (args, _) = filter_args(&field.attrs);
 
-    let wrapper_ty = get_wrapper_ty_ident(&rust).to_string();
+    gen_c_types_inner(&args, args.c_as.as_ref().unwrap_or(&field.ty))
+}
+
+fn generate_struct_field_api(
+    ident: &Ident,
+    field_ident: &Member,
+    field_name: &Ident,
+    field: &Field,
+) -> Option<TokenStream2> {
+    let (args, filtered_attrs) = filter_args(&field.attrs);
 
-    let fn_ident = |action| {
+    let fn_ident = |action: &str| {
         Ident::new(
             &format!(
                 "{}_{action}_{}",
                 ident.to_string().to_case(Case::Snake),
-                field_ident.to_string().to_case(Case::Snake)
+                field_name.to_string().to_case(Case::Snake)
             ),
-            field_ident.span(),
+            field_name.span(),
         )
     };
 
-    let parser = from_c_parser(&format_ident!("value"));
-    let unparser = to_c_parser(&format_ident!("c_value"), &format_ident!("value"));
+    if let Type::Array(array) = args.c_as.as_ref().unwrap_or(&field.ty) {
+        return Some(generate_array_field_api(
+            ident,
+            field_ident,
+            field_name,
+            &fn_ident,
+            &args,
+            &filtered_attrs,
+            array,
+        ));
+    }
 
-    match wrapper_ty.as_str() {
-        "Vec" => {
-            let pusher = fn_ident("push");
-            let getter = fn_ident("get");
-            let remove = fn_ident("remove");
+    if args.c_bytes || is_byte_vec(args.c_as.as_ref().unwrap_or(&field.ty)) {
+        return Some(generate_bytes_field_api(
+            ident,
+            field_ident,
+            field_name,
+            &fn_ident,
+            &filtered_attrs,
+            args.c_as.as_ref().unwrap_or(&field.ty),
+        ));
+    }
 
-            Some(quote::quote! {
-                #(#filtered_attrs)*
-                /// ------
-                /// Pushes the new value to the end of the vector
-                /// ------
-                #[::crops::c_result_fn]
+    if let Some(foreign) = foreign_ty(args.c_as.as_ref().unwrap_or(&field.ty)) {
+        let setter = fn_ident("set");
+        let (name, description) = match foreign.kind {
+            ForeignKind::Callback { .. } => (
+                format_ident!("callback"),
+                " Replaces the callback, `user_data` is passed to every call and released with `destructor`,\n if provided, once the callback is no longer needed.",
+            ),
+            ForeignKind::TraitObject { .. } => (
+                format_ident!("vtable"),
+                " Replaces the implementation with a copy of the vtable, its `user_data` is released with its\n `destructor`, if provided, once the implementation is no longer needed.",
+            ),
+        };
+        let (params, expr) = foreign_params(&name, &foreign, args.c_thread_safe);
+        let doc = if foreign.optional {
+            format!(" Passing a null `{name}` removes the current value.")
+        } else {
+            format!(" Returns an error if `{name}` is null.")
+        };
+
+        return Some(quote::quote! {
+            #(#filtered_attrs)*
+            /// ------
+            #[doc = #description]
+            ///
+            #[doc = #doc]
+            /// ------
+            #[::crops::c_result_fn]
+            fn #setter(source: *mut #ident, #params) -> ::crops::utils::CResult {
+                let value = #expr?;
+
+                ::crops::utils::check_null(source)
+                    .map_err(|e| e.context(stringify!(#ident)))?
+                    .#field_ident = value;
+
+                Ok(())
+            }
+        });
+    }
+
+    let CTypes {
+        rust,
+        from_c,
+        from_c_parser,
+        to_c,
+        to_c_extra,
+        to_c_parser,
+        to_c_owned,
+    } = gen_c_types(field);
+
+    let wrapper_ty = get_wrapper_ty_ident(&rust).to_string();
+
+    let parser = from_c_parser(&format_ident!("value"));
+    let unparser = to_c_parser(&format_ident!("c_value"), &format_ident!("value"));
+
+    let owned_api = args.c_owned.then(|| {
+        let to_c_owned = to_c_owned
+            .as_ref()
+            .expect("c_owned is only supported for String fields");
+        let owner = to_c_owned(&format_ident!("value"));
+
+        let owned_getter = fn_ident("get_owned");
+        let inner_owned_getter = format_ident!("_inner_{owned_getter}");
+
+        let (inputs, args, getter) = match wrapper_ty.as_str() {
+            "Vec" => (
+                quote::quote!(, idx: usize),
+                quote::quote!(, idx),
+                quote::quote!(.get(idx).ok_or_else(::crops::error::Error::index_out_of_range)?),
+            ),
+            "Option" => (
+                quote::quote!(),
+                quote::quote!(),
+                quote::quote!(.as_ref().ok_or_else(::crops::error::Error::option_empty)?),
+            ),
+            _ => (quote::quote!(), quote::quote!(), quote::quote!()),
+        };
+
+        quote::quote! {
+            fn #inner_owned_getter(source: *const #ident #inputs) -> Result<*mut ::crops::_macros::libc::c_char, ::crops::error::Error> {
+                let value = &::crops::utils::check_null_const(source)
+                    .map_err(|e| e.context(stringify!(#ident)))?
+                    .#field_ident
+                    #getter;
+
+                #owner
+            }
+
+            #(#filtered_attrs)*
+            /// ------
+            /// Gets a newly allocated copy of the current value, which must be released with `crops_string_free`.
+            ///
+            /// Returns a null pointer if the value could not be retrieved, the reason is recorded as the last error.
+            /// ------
+            #[no_mangle]
+            pub extern "C" fn #owned_getter(source: *const #ident #inputs) -> *mut ::crops::_macros::libc::c_char {
+                ::crops::error::catch_panic(std::ptr::null_mut(), || {
+                    ::crops::error::or_null(#inner_owned_getter(source #args))
+                })
+            }
+        }
+    });
+
+    let field_api = match wrapper_ty.as_str() {
+        "Option" | "Vec" if nested_wrappers(&rust).is_some() => Some(generate_nested_field_api(
+            ident,
+            field_ident,
+            &fn_ident,
+            &args,
+            &filtered_attrs,
+            nested_wrappers(&rust).unwrap(),
+        )),
+        "Vec" => {
+            let item = match &rust {
+                Type::Path(path) => parse_angle_bracket(path.path.segments.last().unwrap())
+                    .expect("Vectors must have an item type"),
+                _ => unreachable!(),
+            };
+            let item_parser =
+                (gen_c_types_inner(&args, item).from_c_parser)(&format_ident!("value"));
+
+            let pusher = fn_ident("push");
+            let getter = fn_ident("get");
+            let remove = fn_ident("remove");
+            let length = fn_ident("len");
+            let clear = fn_ident("clear");
+            let inserter = fn_ident("insert");
+            let setter = fn_ident("set");
+            let truncate = fn_ident("truncate");
+
+            let copyable = match item {
+                Type::Path(path) => path.path.get_ident().is_some_and(|item| {
+                    COPYABLE.contains(&item.to_string().as_str()) || args.c_by_value
+                }),
+                _ => false,
+            };
+
+            let bulk_api = copyable.then(|| {
+                let extend = fn_ident("extend");
+                let copier = fn_ident("copy");
+
+                quote::quote! {
+                    #(#filtered_attrs)*
+                    /// ------
+                    /// Appends a copy of the `len` values at `value` to the end of the vector
+                    /// ------
+                    #[::crops::c_result_fn]
+                    fn #extend(source: *mut #ident, value: *const #item, len: usize) -> ::crops::utils::CResult {
+                        let value = ::crops::utils::as_slice(value, len)?;
+
+                        ::crops::utils::check_null(source)
+                            .map_err(|e| e.context(stringify!(#ident)))?
+                            .#field_ident
+                            .extend_from_slice(value);
+
+                        Ok(())
+                    }
+
+                    #(#filtered_attrs)*
+                    /// ------
+                    /// Copies all of the values into a buffer of `capacity` values, returns an error if it is too small.
+                    /// ------
+                    #[::crops::c_result_fn]
+                    fn #copier(source: *const #ident, c_value: *mut #item, capacity: usize) -> ::crops::utils::CResult {
+                        let value = &::crops::utils::check_null_const(source)
+                            .map_err(|e| e.context(stringify!(#ident)))?
+                            .#field_ident;
+
+                        ::crops::utils::copy_slice(c_value, capacity, value)
+                    }
+                }
+            });
+
+            Some(quote::quote! {
+                #bulk_api
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Gets the number of elements in the vector
+                /// ------
+                #[::crops::c_result_fn]
+                fn #length(source: *const #ident, c_value: *mut usize) -> ::crops::utils::CResult {
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .len();
+
+                    *::crops::utils::check_null(c_value)? = value;
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Removes all of the elements from the vector
+                /// ------
+                #[::crops::c_result_fn]
+                fn #clear(source: *mut #ident) -> ::crops::utils::CResult {
+                    ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .clear();
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Inserts the value at the provided index, shifting all elements after it along.
+                ///
+                /// The index may be equal to the length of the vector, otherwise returns an error if it doesn't exist.
+                /// ------
+                #[::crops::c_result_fn]
+                fn #inserter(source: *mut #ident, idx: usize, value: #from_c) -> ::crops::utils::CResult {
+                    let source = ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?;
+
+                    if idx > source.#field_ident.len() {
+                        return Err(::crops::error::Error::index_out_of_range());
+                    }
+
+                    let value = {
+                        let value #item_parser;
+                        value
+                    };
+                    source.#field_ident.insert(idx, value);
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Replaces the element at the provided index, if it doesn't exist, returns an error.
+                /// ------
+                #[::crops::c_result_fn]
+                fn #setter(source: *mut #ident, idx: usize, value: #from_c) -> ::crops::utils::CResult {
+                    let element = ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .get_mut(idx)
+                        .ok_or_else(::crops::error::Error::index_out_of_range)?;
+
+                    *element = {
+                        let value #item_parser;
+                        value
+                    };
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Shortens the vector to `len` elements, has no effect if it is already shorter
+                /// ------
+                #[::crops::c_result_fn]
+                fn #truncate(source: *mut #ident, len: usize) -> ::crops::utils::CResult {
+                    ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .truncate(len);
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Pushes the new value to the end of the vector
+                /// ------
+                #[::crops::c_result_fn]
                 fn #pusher(source: *mut #ident, value: #from_c) -> ::crops::utils::CResult {
                     ::crops::utils::check_null(source)
-                        .map_err(|e| format!("{e} ({})", stringify!(#ident)))?
-                        .#field_ident #parser;
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident #parser;
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Gets the current value inside the field
+                /// ------
+                #[::crops::c_result_fn]
+                fn #getter(source: *const #ident, idx: usize, c_value: #to_c #to_c_extra) -> ::crops::utils::CResult {
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .get(idx)
+                        .ok_or_else(::crops::error::Error::index_out_of_range)?;
+
+                    #unparser;
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Removes the element at the provided index, if it doesn't exist, returns an error.
+                ///
+                /// Nothing is removed if the value could not be copied out, or if only its length was queried.
+                /// ------
+                #[::crops::c_result_fn]
+                fn #remove(source: *mut #ident, idx: usize, c_value: #to_c #to_c_extra) -> ::crops::utils::CResult {
+                    let source = ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?;
+
+                    let value = source
+                        .#field_ident
+                        .get(idx)
+                        .ok_or_else(::crops::error::Error::index_out_of_range)?;
+
+                    #unparser;
+
+                    if !c_value.is_null() {
+                        source.#field_ident.remove(idx);
+                    }
+
+                    Ok(())
+                }
+            })
+        }
+        "HashSet" | "BTreeSet" => {
+            let item = match &rust {
+                Type::Path(path) => parse_angle_bracket(path.path.segments.last().unwrap())
+                    .expect("Sets must have an item type"),
+                _ => unreachable!(),
+            };
+            let item_parser =
+                (gen_c_types_inner(&args, item).from_c_parser)(&format_ident!("value"));
+
+            let inserter = fn_ident("insert");
+            let contains = fn_ident("contains");
+            let remove = fn_ident("remove");
+            let length = fn_ident("len");
+            let getter = fn_ident("get");
+
+            Some(quote::quote! {
+                #(#filtered_attrs)*
+                /// ------
+                /// Inserts the value into the set, if it is not already present
+                /// ------
+                #[::crops::c_result_fn]
+                fn #inserter(source: *mut #ident, value: #from_c) -> ::crops::utils::CResult {
+                    ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident #parser;
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Checks whether the value is present in the set
+                /// ------
+                #[::crops::c_result_fn]
+                fn #contains(source: *const #ident, value: #from_c, c_value: *mut bool) -> ::crops::utils::CResult {
+                    let value = {
+                        let value #item_parser;
+                        value
+                    };
+
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .contains(&value);
+
+                    *::crops::utils::check_null(c_value)? = value;
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Removes the value from the set, if it isn't present, returns an error.
+                /// ------
+                #[::crops::c_result_fn]
+                fn #remove(source: *mut #ident, value: #from_c) -> ::crops::utils::CResult {
+                    let value = {
+                        let value #item_parser;
+                        value
+                    };
+
+                    if !::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .remove(&value)
+                    {
+                        return Err(::crops::error::Error::key_not_found());
+                    }
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Gets the number of values in the set
+                /// ------
+                #[::crops::c_result_fn]
+                fn #length(source: *const #ident, c_value: *mut usize) -> ::crops::utils::CResult {
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .len();
+
+                    *::crops::utils::check_null(c_value)? = value;
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Gets the value at the provided index, for iterating over all `len` values.
+                ///
+                /// The order of the values is only stable while the set is not modified.
+                /// ------
+                #[::crops::c_result_fn]
+                fn #getter(source: *const #ident, idx: usize, c_value: #to_c #to_c_extra) -> ::crops::utils::CResult {
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .iter()
+                        .nth(idx)
+                        .ok_or_else(::crops::error::Error::index_out_of_range)?;
+
+                    #unparser;
+
+                    Ok(())
+                }
+            })
+        }
+        "HashMap" | "BTreeMap" => {
+            let (key_ty, value_ty) = match &rust {
+                Type::Path(path) => {
+                    match parse_angle_brackets(path.path.segments.last().unwrap()).as_slice() {
+                        [key_ty, value_ty, ..] => ((*key_ty).clone(), (*value_ty).clone()),
+                        _ => panic!("Maps must have a key and a value type"),
+                    }
+                }
+                _ => unreachable!(),
+            };
+
+            let key = gen_c_types_inner(&args, &key_ty);
+            let value = gen_c_types_inner(&args, &value_ty);
+
+            let key_from_c = &key.from_c;
+            let key_parser = (key.from_c_parser)(&format_ident!("key"));
+            let key_to_c = &key.to_c;
+            let key_to_c_extra = &key.to_c_extra;
+            let key_unparser =
+                (key.to_c_parser)(&format_ident!("c_value"), &format_ident!("value"));
+
+            let value_from_c = &value.from_c;
+            let value_parser = (value.from_c_parser)(&format_ident!("value"));
+            let value_to_c = &value.to_c;
+            let value_to_c_extra = &value.to_c_extra;
+            let value_unparser =
+                (value.to_c_parser)(&format_ident!("c_value"), &format_ident!("value"));
+
+            let inserter = fn_ident("insert");
+            let getter = fn_ident("get");
+            let remove = fn_ident("remove");
+            let contains = fn_ident("contains");
+            let length = fn_ident("len");
+            let keys = fn_ident("keys");
+
+            Some(quote::quote! {
+                #(#filtered_attrs)*
+                /// ------
+                /// Inserts the value at the provided key, replacing any existing value
+                /// ------
+                #[::crops::c_result_fn]
+                fn #inserter(source: *mut #ident, key: #key_from_c, value: #value_from_c) -> ::crops::utils::CResult {
+                    let source = ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?;
+
+                    let key = {
+                        let key #key_parser;
+                        key
+                    };
+                    let value = {
+                        let value #value_parser;
+                        value
+                    };
+
+                    source.#field_ident.insert(key, value);
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Gets the value at the provided key, if it doesn't exist, returns an error.
+                /// ------
+                #[::crops::c_result_fn]
+                fn #getter(source: *const #ident, key: #key_from_c, c_value: #value_to_c #value_to_c_extra) -> ::crops::utils::CResult {
+                    let key = {
+                        let key #key_parser;
+                        key
+                    };
+
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .get(&key)
+                        .ok_or_else(::crops::error::Error::key_not_found)?;
+
+                    #value_unparser;
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Removes the value at the provided key, if it doesn't exist, returns an error.
+                ///
+                /// Nothing is removed if the value could not be copied out, or if only its length was queried.
+                /// ------
+                #[::crops::c_result_fn]
+                fn #remove(source: *mut #ident, key: #key_from_c, c_value: #value_to_c #value_to_c_extra) -> ::crops::utils::CResult {
+                    let key = {
+                        let key #key_parser;
+                        key
+                    };
+
+                    let source = ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?;
+
+                    let value = source
+                        .#field_ident
+                        .get(&key)
+                        .ok_or_else(::crops::error::Error::key_not_found)?;
+
+                    #value_unparser;
+
+                    if !c_value.is_null() {
+                        source.#field_ident.remove(&key);
+                    }
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Checks whether a value exists at the provided key
+                /// ------
+                #[::crops::c_result_fn]
+                fn #contains(source: *const #ident, key: #key_from_c, c_value: *mut bool) -> ::crops::utils::CResult {
+                    let key = {
+                        let key #key_parser;
+                        key
+                    };
+
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .contains_key(&key);
+
+                    *::crops::utils::check_null(c_value)? = value;
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Gets the number of entries
+                /// ------
+                #[::crops::c_result_fn]
+                fn #length(source: *const #ident, c_value: *mut usize) -> ::crops::utils::CResult {
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .len();
+
+                    *::crops::utils::check_null(c_value)? = value;
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Gets the key at the provided index, for iterating over all `len` keys.
+                ///
+                /// The order of the keys is only stable while the map is not modified.
+                /// ------
+                #[::crops::c_result_fn]
+                fn #keys(source: *const #ident, idx: usize, c_value: #key_to_c #key_to_c_extra) -> ::crops::utils::CResult {
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .keys()
+                        .nth(idx)
+                        .ok_or_else(::crops::error::Error::index_out_of_range)?;
+
+                    #key_unparser;
+
+                    Ok(())
+                }
+            })
+        }
+        "Option" => {
+            let setter = fn_ident("replace");
+            let taker = fn_ident("take");
+            let getter = fn_ident("get");
+
+            Some(quote::quote! {
+                #(#filtered_attrs)*
+                /// ------
+                /// Replaces the current value with the provided value
+                /// ------
+                #[::crops::c_result_fn]
+                fn #setter(source: *mut #ident, value: #from_c) -> ::crops::utils::CResult {
+                    ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident #parser;
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Takes the value, removing it from the option.
+                ///
+                /// The option is left untouched if the value could not be copied out, or if only its length was queried.
+                /// ------
+                #[::crops::c_result_fn]
+                fn #taker(source: *mut #ident, c_value: #to_c #to_c_extra) -> ::crops::utils::CResult {
+                    let source = ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?;
+
+                    let value = source
+                        .#field_ident
+                        .as_ref()
+                        .ok_or_else(::crops::error::Error::option_empty)?;
+
+                    #unparser;
+
+                    if !c_value.is_null() {
+                        source.#field_ident.take();
+                    }
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Gets the current value within the option.
+                /// ------
+                #[::crops::c_result_fn]
+                fn #getter(source: *const #ident, c_value: #to_c #to_c_extra) -> ::crops::utils::CResult {
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .as_ref()
+                        .ok_or_else(::crops::error::Error::option_empty)?;
+
+                    #unparser;
+
+                    Ok(())
+                }
+            })
+        }
+        _ => {
+            let setter = fn_ident("with");
+            let getter = fn_ident("get");
+
+            // Opaque values can also be moved in and out of their own boxes, rather than copied
+            let opaque = !(args.c_enum || args.c_by_value || args.c_int_enum)
+                && match &rust {
+                    Type::Path(path) => path.path.get_ident().is_some_and(|ty| {
+                        let ty = ty.to_string();
+                        !COPYABLE.contains(&ty.as_str())
+                            && !["String", "char", "i128", "u128"].contains(&ty.as_str())
+                    }),
+                    _ => false,
+                };
+
+            let move_api = opaque.then(|| {
+                let set_taker = Ident::new(
+                    &format!(
+                        "{}_set_{}_take",
+                        ident.to_string().to_case(Case::Snake),
+                        field_name.to_string().to_case(Case::Snake)
+                    ),
+                    field_name.span(),
+                );
+                let swapper = fn_ident("swap");
+
+                // Taking leaves the default behind, so is only generated for fields asking for it
+                let take_api = args.c_take.then(|| {
+                    let taker = fn_ident("take");
+
+                    quote::quote! {
+                        #(#filtered_attrs)*
+                        /// ------
+                        /// Moves the value out of the field into a new box written to `c_value`, leaving the default
+                        /// value in the field. The box must be freed by the caller.
+                        /// ------
+                        #[::crops::c_result_fn]
+                        fn #taker(source: *mut #ident, c_value: *mut *mut #rust) -> ::crops::utils::CResult {
+                            let source = ::crops::utils::check_null(source)
+                                .map_err(|e| e.context(stringify!(#ident)))?;
+                            let c_value = ::crops::utils::check_null(c_value)?;
+
+                            *c_value = Box::into_raw(Box::new(std::mem::take(&mut source.#field_ident)));
+
+                            Ok(())
+                        }
+                    }
+                });
+
+                quote::quote! {
+                    #(#filtered_attrs)*
+                    /// ------
+                    /// Moves the boxed value into the field, freeing the box, so `value` must not be used again.
+                    ///
+                    /// `value` is left untouched if an error is returned.
+                    /// ------
+                    #[::crops::c_result_fn]
+                    fn #set_taker(source: *mut #ident, value: *mut #rust) -> ::crops::utils::CResult {
+                        let source = ::crops::utils::check_null(source)
+                            .map_err(|e| e.context(stringify!(#ident)))?;
+
+                        source.#field_ident = ::crops::utils::take_box(value)?;
+
+                        Ok(())
+                    }
+
+                    #(#filtered_attrs)*
+                    /// ------
+                    /// Swaps the value of the field with the boxed `c_value`, without copying either.
+                    /// ------
+                    #[::crops::c_result_fn]
+                    fn #swapper(source: *mut #ident, c_value: *mut #rust) -> ::crops::utils::CResult {
+                        let source = ::crops::utils::check_null(source)
+                            .map_err(|e| e.context(stringify!(#ident)))?;
+
+                        std::mem::swap(&mut source.#field_ident, ::crops::utils::check_null(c_value)?);
+
+                        Ok(())
+                    }
+
+                    #take_api
+                }
+            });
+
+            // C can't create or free boxes of types passed by value, so they must be marked as such
+            let by_value_check = opaque.then(|| {
+                let message = format!(
+                    "{ident}.{field_name} holds a type passed by value, so must be marked #[c_builder(c_by_value)]"
+                );
+                quote::quote! {
+                    const _: () = {
+                        use ::crops::traits::MaybeByValue as _;
+                        assert!(!<#rust>::CROPS_BY_VALUE, #message);
+                    };
+                }
+            });
+
+            Some(quote::quote! {
+                #by_value_check
+
+                #move_api
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Replaces the current value with the provided value
+                /// ------
+                #[::crops::c_result_fn]
+                fn #setter(source: *mut #ident, value: #from_c) -> ::crops::utils::CResult {
+                    ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident #parser;
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Gets the current value
+                /// ------
+                #[::crops::c_result_fn]
+                fn #getter(source: *const #ident, c_value: #to_c #to_c_extra) -> ::crops::utils::CResult {
+                    let value = &::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident;
+
+                    #unparser;
+
+                    Ok(())
+                }
+            })
+        }
+    };
+
+    field_api.map(|field_api| quote::quote!(#field_api #owned_api))
+}
+
+/// Byte buffers, copied in and out as a pointer and length rather than byte by byte
+fn generate_bytes_field_api(
+    ident: &Ident,
+    field_ident: &Member,
+    field_name: &Ident,
+    fn_ident: &dyn Fn(&str) -> Ident,
+    filtered_attrs: &[&Attribute],
+    ty: &Type,
+) -> TokenStream2 {
+    if let Type::Reference(_) = ty {
+        panic!("Byte buffers must own their bytes, use Vec<u8> or Box<[u8]> in place of &[u8]")
+    }
+
+    let setter = fn_ident("set");
+    let copier = fn_ident("copy");
+    let viewer = fn_ident("view");
+    let length = Ident::new(
+        &format!(
+            "{}_get_{}_len",
+            ident.to_string().to_case(Case::Snake),
+            field_name.to_string().to_case(Case::Snake)
+        ),
+        field_name.span(),
+    );
+
+    quote::quote! {
+        #(#filtered_attrs)*
+        /// ------
+        /// Replaces the current bytes with a copy of the `len` bytes at `value`
+        /// ------
+        #[::crops::c_result_fn]
+        fn #setter(source: *mut #ident, value: *const u8, len: usize) -> ::crops::utils::CResult {
+            let value = ::crops::utils::as_slice(value, len)?;
+
+            ::crops::utils::check_null(source)
+                .map_err(|e| e.context(stringify!(#ident)))?
+                .#field_ident = value.to_vec().into();
+
+            Ok(())
+        }
+
+        #(#filtered_attrs)*
+        /// ------
+        /// Gets the number of bytes currently stored
+        /// ------
+        #[::crops::c_result_fn]
+        fn #length(source: *const #ident, c_value: *mut usize) -> ::crops::utils::CResult {
+            let value: &[u8] = ::crops::utils::check_null_const(source)
+                .map_err(|e| e.context(stringify!(#ident)))?
+                .#field_ident
+                .as_ref();
+
+            *::crops::utils::check_null(c_value)? = value.len();
+
+            Ok(())
+        }
+
+        #(#filtered_attrs)*
+        /// ------
+        /// Copies the current bytes into a buffer of `capacity` bytes, returns an error if it is too small.
+        /// ------
+        #[::crops::c_result_fn]
+        fn #copier(source: *const #ident, c_value: *mut u8, capacity: usize) -> ::crops::utils::CResult {
+            let value: &[u8] = ::crops::utils::check_null_const(source)
+                .map_err(|e| e.context(stringify!(#ident)))?
+                .#field_ident
+                .as_ref();
+
+            ::crops::utils::copy_slice(c_value, capacity, value)
+        }
+
+        #(#filtered_attrs)*
+        /// ------
+        /// Borrows the current bytes without copying them.
+        ///
+        /// The view is only valid until the model is next modified or freed.
+        /// ------
+        #[::crops::c_result_fn]
+        fn #viewer(source: *const #ident, c_value: *mut *const u8, c_length: *mut usize) -> ::crops::utils::CResult {
+            let value: &[u8] = ::crops::utils::check_null_const(source)
+                .map_err(|e| e.context(stringify!(#ident)))?
+                .#field_ident
+                .as_ref();
+
+            let c_value = ::crops::utils::check_null(c_value)?;
+            *::crops::utils::check_null(c_length)? = value.len();
+            *c_value = value.as_ptr();
+
+            Ok(())
+        }
+    }
+}
+
+/// Arrays of copyable types, exposed along with a constant holding their length
+fn generate_array_field_api(
+    ident: &Ident,
+    field_ident: &Member,
+    field_name: &Ident,
+    fn_ident: &dyn Fn(&str) -> Ident,
+    args: &CBuilderFieldArgs,
+    filtered_attrs: &[&Attribute],
+    array: &syn::TypeArray,
+) -> TokenStream2 {
+    let syn::TypeArray { elem, len, .. } = array;
+
+    let copyable = match elem.as_ref() {
+        Type::Path(path) => path
+            .path
+            .get_ident()
+            .is_some_and(|elem| COPYABLE.contains(&elem.to_string().as_str()) || args.c_by_value),
+        _ => false,
+    };
+    if !copyable {
+        panic!("Only arrays of copyable types are supported");
+    }
+
+    let item_parser = if args.c_by_value {
+        quote::quote!(::crops::traits::by_value(value))
+    } else {
+        quote::quote!(value)
+    };
+
+    let length = Ident::new(
+        &format!(
+            "{}_{}_LEN",
+            ident.to_string().to_case(Case::UpperSnake),
+            field_name.to_string().to_case(Case::UpperSnake)
+        ),
+        field_name.span(),
+    );
+    let getter = fn_ident("get");
+    let setter = fn_ident("set");
+    let item_getter = fn_ident("get_item");
+    let item_setter = fn_ident("set_item");
+
+    let length_doc = format!(
+        " The number of elements in `{ident}::{}`",
+        field_ident.to_token_stream()
+    );
+    let getter_doc = format!(
+        " Copies all of the elements into a buffer, which must have room for `{length}` elements"
+    );
+    let setter_doc =
+        format!(" Replaces all of the elements with a copy of the `{length}` elements at `value`");
+
+    quote::quote! {
+        /// ------
+        #[doc = #length_doc]
+        /// ------
+        pub const #length: usize = #len;
+
+        #(#filtered_attrs)*
+        /// ------
+        #[doc = #getter_doc]
+        /// ------
+        #[::crops::c_result_fn]
+        fn #getter(source: *const #ident, c_value: *mut #elem) -> ::crops::utils::CResult {
+            let value = &::crops::utils::check_null_const(source)
+                .map_err(|e| e.context(stringify!(#ident)))?
+                .#field_ident;
+
+            ::crops::utils::copy_slice(c_value, #length, value)
+        }
+
+        #(#filtered_attrs)*
+        /// ------
+        #[doc = #setter_doc]
+        /// ------
+        #[::crops::c_result_fn]
+        fn #setter(source: *mut #ident, value: *const #elem) -> ::crops::utils::CResult {
+            let value = ::crops::utils::as_slice(value, #length)?;
+
+            ::crops::utils::check_null(source)
+                .map_err(|e| e.context(stringify!(#ident)))?
+                .#field_ident
+                .copy_from_slice(value);
+
+            Ok(())
+        }
+
+        #(#filtered_attrs)*
+        /// ------
+        /// Gets the element at the provided index, if it doesn't exist, returns an error.
+        /// ------
+        #[::crops::c_result_fn]
+        fn #item_getter(source: *const #ident, idx: usize, c_value: *mut #elem) -> ::crops::utils::CResult {
+            let value = ::crops::utils::check_null_const(source)
+                .map_err(|e| e.context(stringify!(#ident)))?
+                .#field_ident
+                .get(idx)
+                .ok_or_else(::crops::error::Error::index_out_of_range)?;
+
+            *::crops::utils::check_null(c_value)? = *value;
+
+            Ok(())
+        }
+
+        #(#filtered_attrs)*
+        /// ------
+        /// Replaces the element at the provided index, if it doesn't exist, returns an error.
+        /// ------
+        #[::crops::c_result_fn]
+        fn #item_setter(source: *mut #ident, idx: usize, value: #elem) -> ::crops::utils::CResult {
+            let element = ::crops::utils::check_null(source)
+                .map_err(|e| e.context(stringify!(#ident)))?
+                .#field_ident
+                .get_mut(idx)
+                .ok_or_else(::crops::error::Error::index_out_of_range)?;
+
+            *element = #item_parser;
+
+            Ok(())
+        }
+    }
+}
+
+/// The outer and inner wrappers of a nested `Option` / `Vec` field, along with the innermost type
+fn nested_wrappers(ty: &Type) -> Option<(String, String, &Type)> {
+    fn unwrap(ty: &Type) -> Option<(String, &Type)> {
+        match ty {
+            Type::Path(path) => {
+                let last = path.path.segments.last().unwrap();
+                let wrapper = last.ident.to_string();
+                matches!(wrapper.as_str(), "Option" | "Vec")
+                    .then(|| parse_angle_bracket(last).map(|inner| (wrapper, inner)))
+                    .flatten()
+            }
+            _ => None,
+        }
+    }
+
+    let (outer, ty) = unwrap(ty)?;
+    let (inner, ty) = unwrap(ty)?;
+    Some((outer, inner, ty))
+}
+
+/// Composed API for an `Option` or `Vec` of an `Option` or `Vec`, reaching through both layers
+fn generate_nested_field_api(
+    ident: &Ident,
+    field_ident: &Member,
+    fn_ident: &dyn Fn(&str) -> Ident,
+    args: &CBuilderFieldArgs,
+    filtered_attrs: &[&Attribute],
+    (outer, inner, item): (String, String, &Type),
+) -> TokenStream2 {
+    if args.c_owned {
+        panic!("c_owned is not supported for nested containers");
+    }
+
+    let CTypes {
+        from_c,
+        from_c_parser,
+        to_c,
+        to_c_extra,
+        to_c_parser,
+        ..
+    } = gen_c_types_inner(args, item);
+
+    let parser = from_c_parser(&format_ident!("value"));
+    let unparser = to_c_parser(&format_ident!("c_value"), &format_ident!("value"));
+
+    // How the inner container is reached through the outer one
+    let (outer_inputs, outer_get, outer_get_mut, outer_api) = match outer.as_str() {
+        "Option" => {
+            let is_some = fn_ident("is_some");
+            let clear = fn_ident("clear");
+
+            (
+                quote::quote!(),
+                quote::quote!(.as_ref().ok_or_else(::crops::error::Error::option_empty)?),
+                quote::quote!(.as_mut().ok_or_else(::crops::error::Error::option_empty)?),
+                quote::quote! {
+                    #(#filtered_attrs)*
+                    /// ------
+                    /// Checks whether the option currently holds a value
+                    /// ------
+                    #[::crops::c_result_fn]
+                    fn #is_some(source: *const #ident, c_value: *mut bool) -> ::crops::utils::CResult {
+                        let value = ::crops::utils::check_null_const(source)
+                            .map_err(|e| e.context(stringify!(#ident)))?
+                            .#field_ident
+                            .is_some();
+
+                        *::crops::utils::check_null(c_value)? = value;
+
+                        Ok(())
+                    }
+
+                    #(#filtered_attrs)*
+                    /// ------
+                    /// Removes the value from the option
+                    /// ------
+                    #[::crops::c_result_fn]
+                    fn #clear(source: *mut #ident) -> ::crops::utils::CResult {
+                        ::crops::utils::check_null(source)
+                            .map_err(|e| e.context(stringify!(#ident)))?
+                            .#field_ident = None;
+
+                        Ok(())
+                    }
+                },
+            )
+        }
+        _ => {
+            let length = fn_ident("len");
+            let clear = fn_ident("clear");
+
+            (
+                quote::quote!(idx: usize,),
+                quote::quote!(.get(idx).ok_or_else(::crops::error::Error::index_out_of_range)?),
+                quote::quote!(.get_mut(idx).ok_or_else(::crops::error::Error::index_out_of_range)?),
+                quote::quote! {
+                    #(#filtered_attrs)*
+                    /// ------
+                    /// Gets the number of elements in the vector
+                    /// ------
+                    #[::crops::c_result_fn]
+                    fn #length(source: *const #ident, c_value: *mut usize) -> ::crops::utils::CResult {
+                        let value = ::crops::utils::check_null_const(source)
+                            .map_err(|e| e.context(stringify!(#ident)))?
+                            .#field_ident
+                            .len();
+
+                        *::crops::utils::check_null(c_value)? = value;
+
+                        Ok(())
+                    }
+
+                    #(#filtered_attrs)*
+                    /// ------
+                    /// Removes all of the elements from the vector
+                    /// ------
+                    #[::crops::c_result_fn]
+                    fn #clear(source: *mut #ident) -> ::crops::utils::CResult {
+                        ::crops::utils::check_null(source)
+                            .map_err(|e| e.context(stringify!(#ident)))?
+                            .#field_ident
+                            .clear();
+
+                        Ok(())
+                    }
+                },
+            )
+        }
+    };
+
+    let inner_api = match (outer.as_str(), inner.as_str()) {
+        ("Option", "Option") => panic!("Option<Option<T>> fields are not supported"),
+        ("Option", _) | ("Vec", "Vec") => {
+            // Functions on the inner vector are qualified where they'd clash with the outer vector's
+            let (length, pusher, inner_idx) = if outer == "Vec" {
+                (
+                    fn_ident("item_len"),
+                    fn_ident("push_item"),
+                    format_ident!("item_idx"),
+                )
+            } else {
+                (fn_ident("len"), fn_ident("push"), format_ident!("idx"))
+            };
+            let getter = fn_ident("get");
+            let setter = fn_ident("set");
+
+            // Pushing to an empty option starts a new vector, pushing to the outer vector adds an empty one
+            let (push_target, outer_pusher) = if outer == "Vec" {
+                let outer_pusher = fn_ident("push_empty");
+                (
+                    outer_get_mut.clone(),
+                    quote::quote! {
+                        #(#filtered_attrs)*
+                        /// ------
+                        /// Pushes a new empty vector to the end of the vector
+                        /// ------
+                        #[::crops::c_result_fn]
+                        fn #outer_pusher(source: *mut #ident) -> ::crops::utils::CResult {
+                            ::crops::utils::check_null(source)
+                                .map_err(|e| e.context(stringify!(#ident)))?
+                                .#field_ident
+                                .push(Vec::new());
+
+                            Ok(())
+                        }
+                    },
+                )
+            } else {
+                (
+                    quote::quote!(.get_or_insert_with(Vec::new)),
+                    quote::quote!(),
+                )
+            };
+
+            quote::quote! {
+                #outer_pusher
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Gets the number of elements in the inner vector
+                /// ------
+                #[::crops::c_result_fn]
+                fn #length(source: *const #ident, #outer_inputs c_value: *mut usize) -> ::crops::utils::CResult {
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        #outer_get
+                        .len();
+
+                    *::crops::utils::check_null(c_value)? = value;
+
+                    Ok(())
+                }
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Pushes the new value to the end of the inner vector
+                /// ------
+                #[::crops::c_result_fn]
+                fn #pusher(source: *mut #ident, #outer_inputs value: #from_c) -> ::crops::utils::CResult {
+                    let value = {
+                        let value #parser;
+                        value
+                    };
+
+                    ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        #push_target
+                        .push(value);
 
                     Ok(())
                 }
 
                 #(#filtered_attrs)*
                 /// ------
-                /// Gets the current value inside the field
+                /// Gets the value at the provided index of the inner vector
                 /// ------
                 #[::crops::c_result_fn]
-                fn #getter(source: *const #ident, idx: usize, c_value: #to_c) -> ::crops::utils::CResult {
+                fn #getter(source: *const #ident, #outer_inputs #inner_idx: usize, c_value: #to_c #to_c_extra) -> ::crops::utils::CResult {
                     let value = ::crops::utils::check_null_const(source)
-                        .map_err(|e| format!("{e} ({})", stringify!(#ident)))?
+                        .map_err(|e| e.context(stringify!(#ident)))?
                         .#field_ident
-                        .get(idx)
-                        .ok_or_else(|| format!("Index Out of Range"))?;
+                        #outer_get
+                        .get(#inner_idx)
+                        .ok_or_else(::crops::error::Error::index_out_of_range)?;
 
                     #unparser;
 
@@ -452,136 +2553,299 @@ fn generate_struct_field_api(ident: &Ident, field: &Field) -> Option<TokenStream
 
                 #(#filtered_attrs)*
                 /// ------
-                /// Removes the element at the provided index, if it doesn't exist, returns an error.
+                /// Replaces the value at the provided index of the inner vector
                 /// ------
                 #[::crops::c_result_fn]
-                fn #remove(source: *mut #ident, idx: usize, c_value: #to_c) -> ::crops::utils::CResult {
-                    let _ = ::crops::utils::check_null_const(source)
-                        .map_err(|e| format!("{e} ({})", stringify!(#ident)))?
-                        .#field_ident
-                        .get(idx)
-                        .ok_or_else(|| format!("Index Out of Range"))?;
-
-                    let value = &::crops::utils::check_null(source)
-                        .map_err(|e| format!("{e} ({})", stringify!(#ident)))?
+                fn #setter(source: *mut #ident, #outer_inputs #inner_idx: usize, value: #from_c) -> ::crops::utils::CResult {
+                    let element = ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
                         .#field_ident
-                        .remove(idx);
+                        #outer_get_mut
+                        .get_mut(#inner_idx)
+                        .ok_or_else(::crops::error::Error::index_out_of_range)?;
 
-                    #unparser;
+                    *element = {
+                        let value #parser;
+                        value
+                    };
 
                     Ok(())
                 }
-            })
+            }
         }
-        "Option" => {
-            let setter = fn_ident("replace");
-            let taker = fn_ident("take");
+        _ => {
+            let pusher = fn_ident("push");
+            let push_none = fn_ident("push_none");
+            let is_some = fn_ident("is_some");
             let getter = fn_ident("get");
+            let setter = fn_ident("set");
+            let set_none = fn_ident("set_none");
 
-            Some(quote::quote! {
+            quote::quote! {
                 #(#filtered_attrs)*
                 /// ------
-                /// Replaces the current value with the provided value
+                /// Pushes the new value to the end of the vector
                 /// ------
                 #[::crops::c_result_fn]
-                fn #setter(source: *mut #ident, value: #from_c) -> ::crops::utils::CResult {
+                fn #pusher(source: *mut #ident, value: #from_c) -> ::crops::utils::CResult {
+                    let value = {
+                        let value #parser;
+                        value
+                    };
+
                     ::crops::utils::check_null(source)
-                        .map_err(|e| format!("{e} ({})", stringify!(#ident)))?
-                        .#field_ident #parser;
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        .push(Some(value));
 
                     Ok(())
                 }
 
                 #(#filtered_attrs)*
                 /// ------
-                /// Takes the value, removing it from the option.
+                /// Pushes an empty option to the end of the vector
                 /// ------
                 #[::crops::c_result_fn]
-                fn #taker(source: *mut #ident, c_value: #to_c) -> ::crops::utils::CResult {
-                    let value = &::crops::utils::check_null(source)
-                        .map_err(|e| format!("{e} ({})", stringify!(#ident)))?
+                fn #push_none(source: *mut #ident) -> ::crops::utils::CResult {
+                    ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
                         .#field_ident
-                        .take()
-                        .ok_or_else(|| format!("Option Empty"))?;
-
-                    #unparser;
+                        .push(None);
 
                     Ok(())
                 }
 
                 #(#filtered_attrs)*
                 /// ------
-                /// Gets the current value within the option.
+                /// Checks whether the option at the provided index holds a value
                 /// ------
                 #[::crops::c_result_fn]
-                fn #getter(source: *const #ident, c_value: #to_c) -> ::crops::utils::CResult {
-                    if let Some(value) = ::crops::utils::check_null_const(source)
-                        .map_err(|e| format!("{e} ({})", stringify!(#ident)))?
-                        .#field_ident.as_ref()
-                    {
-                        #unparser;
-                    }
+                fn #is_some(source: *const #ident, idx: usize, c_value: *mut bool) -> ::crops::utils::CResult {
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        #outer_get
+                        .is_some();
+
+                    *::crops::utils::check_null(c_value)? = value;
 
                     Ok(())
                 }
-            })
-        }
-        _ => {
-            let setter = fn_ident("with");
-            let getter = fn_ident("get");
 
-            Some(quote::quote! {
                 #(#filtered_attrs)*
                 /// ------
-                /// Replaces the current value with the provided value
+                /// Gets the value within the option at the provided index
                 /// ------
                 #[::crops::c_result_fn]
-                fn #setter(source: *mut #ident, value: #from_c) -> ::crops::utils::CResult {
-                    ::crops::utils::check_null(source)
-                        .map_err(|e| format!("{e} ({})", stringify!(#ident)))?
-                        .#field_ident #parser;
+                fn #getter(source: *const #ident, idx: usize, c_value: #to_c #to_c_extra) -> ::crops::utils::CResult {
+                    let value = ::crops::utils::check_null_const(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        #outer_get
+                        .as_ref()
+                        .ok_or_else(::crops::error::Error::option_empty)?;
+
+                    #unparser;
 
                     Ok(())
                 }
 
                 #(#filtered_attrs)*
                 /// ------
-                /// Gets the current value
+                /// Replaces the option at the provided index with the provided value
                 /// ------
                 #[::crops::c_result_fn]
-                fn #getter(source: *const #ident, c_value: #to_c) -> ::crops::utils::CResult {
-                    let value = &::crops::utils::check_null_const(source)
-                        .map_err(|e| format!("{e} ({})", stringify!(#ident)))?
-                        .#field_ident;
+                fn #setter(source: *mut #ident, idx: usize, value: #from_c) -> ::crops::utils::CResult {
+                    let element = ::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        #outer_get_mut;
 
-                    #unparser;
+                    *element = Some({
+                        let value #parser;
+                        value
+                    });
 
                     Ok(())
                 }
-            })
+
+                #(#filtered_attrs)*
+                /// ------
+                /// Empties the option at the provided index
+                /// ------
+                #[::crops::c_result_fn]
+                fn #set_none(source: *mut #ident, idx: usize) -> ::crops::utils::CResult {
+                    *::crops::utils::check_null(source)
+                        .map_err(|e| e.context(stringify!(#ident)))?
+                        .#field_ident
+                        #outer_get_mut = None;
+
+                    Ok(())
+                }
+            }
         }
+    };
+
+    quote::quote!(#outer_api #inner_api)
+}
+
+fn has_repr(attrs: &[Attribute], repr: &str) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("repr")
+            && attr
+                .parse_args_with(Punctuated::<Ident, syn::Token![,]>::parse_terminated)
+                .is_ok_and(|reprs| reprs.iter().any(|r| r == repr))
+    })
+}
+
+/// Fields are exposed under their own name, the name given by `c_name`, or their index
+fn field_name(idx: usize, field: &Field) -> (Ident, Member) {
+    let (args, _) = filter_args(&field.attrs);
+
+    match &field.ident {
+        Some(ident) => (
+            args.c_name.unwrap_or(ident.clone()),
+            Member::Named(ident.clone()),
+        ),
+        // The leading underscore is dropped when snake casing, giving e.g. `meters_get_0`
+        None => (
+            args.c_name
+                .unwrap_or_else(|| format_ident!("_{idx}", span = field.span())),
+            Member::Unnamed(idx.into()),
+        ),
     }
 }
 
-fn derive_c_builder_struct(ident: Ident, attrs: Vec<Attribute>, s: DataStruct) -> TokenStream {
-    let DataStruct { fields, .. } = s;
+/// Types passed by value rather than behind a pointer, either transparent newtypes or plain old data
+fn derive_c_builder_by_value(
+    ident: Ident,
+    args: CBuilderArgs,
+    filtered_attrs: Vec<Attribute>,
+    fields: Fields,
+    zeroed: Option<TokenStream2>,
+) -> TokenStream2 {
+    let inner = fields.iter().map(|field| &field.ty);
+
+    // Fields may not accept all zero bytes, e.g. C enums without a 0 discriminant, so are zeroed
+    // one by one
+    let zeroed = zeroed.unwrap_or_else(|| {
+        let value = match &fields {
+            Fields::Named(named) => {
+                let names = named.named.iter().map(|field| &field.ident);
+                quote::quote!(Self { #(#names: ::crops::traits::ByValue::zeroed()),* })
+            }
+            Fields::Unnamed(unnamed) => {
+                let values = unnamed
+                    .unnamed
+                    .iter()
+                    .map(|_| quote::quote!(::crops::traits::ByValue::zeroed()));
+                quote::quote!(Self(#(#values),*))
+            }
+            Fields::Unit => quote::quote!(Self),
+        };
 
-    let fields = match fields {
-        Fields::Named(named) => named
-            .named
-            .into_iter()
-            .map(|field| (field.ident.clone().unwrap().to_string(), field))
-            .collect::<HashMap<_, _>>(),
-        _ => panic!("Only named fields supported"),
-    };
+        quote::quote! {
+            fn zeroed() -> Self {
+                #value
+            }
+        }
+    });
+
+    let c_default = args.c_new.then(|| {
+        let new_ident = syn::Ident::new(
+            &format!("{}_default", ident.to_string().to_case(Case::Snake)),
+            ident.span(),
+        );
+        quote::quote!(
+            #(#filtered_attrs)*
+            /// ------
+            /// Construct a new value
+            /// ------
+            #[no_mangle]
+            pub extern "C" fn #new_ident() -> #ident {
+                ::crops::error::catch_panic(::crops::traits::ByValue::zeroed(), #ident::default)
+            }
+        )
+    });
+
+    let c_debug = args.c_debug.then(|| {
+        let debug_ident = syn::Ident::new(
+            &format!("{}_debug", ident.to_string().to_case(Case::Snake)),
+            ident.span(),
+        );
+
+        quote::quote!(
+            #(#filtered_attrs)*
+            /// ------
+            /// Print a debug of the value to stdout
+            /// ------
+            #[no_mangle]
+            pub extern "C" fn #debug_ident(s: #ident) {
+                ::crops::error::catch_panic((), || println!("{:?}", s))
+            }
+        )
+    });
+
+    quote::quote! {
+        impl #ident {
+            #[doc(hidden)]
+            pub const CROPS_BY_VALUE: bool = true;
+        }
 
-    let by_type = fields
-        .values()
-        .filter_map(|field| generate_struct_field_api(&ident, field));
+        // Safety: the type is `repr(transparent)` or `repr(C)`, and made up of `ByValue` types
+        unsafe impl ::crops::traits::ByValue for #ident
+        where
+            #(#inner: ::crops::traits::ByValue),*
+        {
+            #zeroed
+        }
+
+        #c_default
+
+        #c_debug
+    }
+}
+
+fn derive_c_builder_struct(ident: Ident, attrs: Vec<Attribute>, s: DataStruct) -> TokenStream {
+    let DataStruct { fields, .. } = s;
 
     let mut args = CBuilderArgs::default();
+    let transparent = has_repr(&attrs, "transparent");
+    let repr_c = has_repr(&attrs, "C");
     let filtered_attrs = args.extract(attrs);
 
+    if args.c_transparent {
+        if !transparent {
+            panic!("Transparent types must be #[repr(transparent)]");
+        }
+        if fields.len() != 1 {
+            panic!("Transparent types must have exactly one field");
+        }
+        return derive_c_builder_by_value(ident, args, filtered_attrs, fields, None).into();
+    }
+
+    if args.c_pod {
+        if !repr_c {
+            panic!("Plain old data types must be #[repr(C)]");
+        }
+        return derive_c_builder_by_value(ident, args, filtered_attrs, fields, None).into();
+    }
+
+    let fields = fields
+        .into_iter()
+        .enumerate()
+        .map(|(idx, field)| {
+            let (name, member) = field_name(idx, &field);
+            (name.to_string(), (name, member, field))
+        })
+        .collect::<HashMap<_, _>>();
+
+    let by_type = fields.values().filter_map(|(name, member, field)| {
+        let check = field_int_enum_check(&ident, name, field);
+        generate_struct_field_api(&ident, member, name, field)
+            .map(|field_api| quote::quote!(#check #field_api))
+    });
+
     let c_default = args.c_new.then(|| {
         let new_ident = syn::Ident::new(
             &format!("{}_default", ident.to_string().to_case(Case::Snake)),
@@ -594,7 +2858,7 @@ fn derive_c_builder_struct(ident: Ident, attrs: Vec<Attribute>, s: DataStruct) -
             /// ------
             #[no_mangle]
             pub extern "C" fn #new_ident() -> *mut #ident {
-                Box::into_raw(Box::default())
+                ::crops::error::catch_panic(std::ptr::null_mut(), || Box::into_raw(Box::default()))
             }
         )
     });
@@ -612,7 +2876,7 @@ fn derive_c_builder_struct(ident: Ident, attrs: Vec<Attribute>, s: DataStruct) -
             /// ------
             #[no_mangle]
             pub extern "C" fn #clone_ident(s: &#ident) -> *mut #ident {
-                Box::into_raw(Box::new(s.clone()))
+                ::crops::error::catch_panic(std::ptr::null_mut(), || Box::into_raw(Box::new(s.clone())))
             }
         )
     });
@@ -630,12 +2894,13 @@ fn derive_c_builder_struct(ident: Ident, attrs: Vec<Attribute>, s: DataStruct) -
             /// ------
             #[no_mangle]
             pub extern "C" fn #debug_ident(s: &#ident) {
-                println!("{:?}", s);
+                ::crops::error::catch_panic((), || println!("{:?}", s))
             }
         )
     });
 
-    let c_free = args.c_debug.then(|| {
+    // Opaque types can always be freed, even if they can't be debugged
+    let c_free = {
         let free_ident = syn::Ident::new(
             &format!("{}_free", ident.to_string().to_case(Case::Snake)),
             ident.span(),
@@ -652,10 +2917,10 @@ fn derive_c_builder_struct(ident: Ident, attrs: Vec<Attribute>, s: DataStruct) -
             /// ------
             #[no_mangle]
             pub unsafe extern "C" fn #free_ident(s: *mut #ident) {
-                ::crops::c_free!(s);
+                ::crops::error::catch_panic((), || ::crops::c_free!(s))
             }
         )
-    });
+    };
 
     let extra_constructors = args.c_constructors.iter().map(|constructor| {
         let constructor_ident = syn::Ident::new(
@@ -673,10 +2938,22 @@ fn derive_c_builder_struct(ident: Ident, attrs: Vec<Attribute>, s: DataStruct) -
         let inner_constructor =
             syn::Ident::new(&format!("inner_{}", constructor_ident), ident.span());
 
+        let try_constructor_ident = syn::Ident::new(
+            &format!(
+                "{}_try_from_{}",
+                ident.to_string().to_case(Case::Snake),
+                constructor
+                    .iter()
+                    .map(|ident| ident.to_string().to_case(Case::Snake))
+                    .join("_")
+            ),
+            ident.span(),
+        );
+
         let (inputs, setters): (Vec<_>, Vec<_>) = constructor
             .iter()
             .map(|ident| -> (syn::FnArg, proc_macro2::TokenStream) {
-                let field = fields
+                let (_, member, field) = fields
                     .get(&ident.to_string())
                     .expect("Constructor fields must match struct");
 
@@ -688,12 +2965,12 @@ fn derive_c_builder_struct(ident: Ident, attrs: Vec<Attribute>, s: DataStruct) -
 
                 let parser = from_c_parser(ident);
 
-                (parse_quote!(#ident: #from_c), parse_quote!(.#ident #parser))
+                (parse_quote!(#ident: #from_c), parse_quote!(.#member #parser))
             })
             .unzip();
 
         quote::quote!(
-            fn #inner_constructor(#(#inputs),*) -> Result<#ident, String> {
+            fn #inner_constructor(#(#inputs),*) -> Result<#ident, ::crops::error::Error> {
                 let mut res = #ident::default();
 
                 #(res #setters;)*
@@ -703,13 +2980,32 @@ fn derive_c_builder_struct(ident: Ident, attrs: Vec<Attribute>, s: DataStruct) -
             #(#filtered_attrs)*
             /// ------
             /// Unique Constructor with sepcific fields
+            ///
+            /// Returns a null pointer if any of the fields are invalid, the reason is recorded as the last error.
             /// ------
             #[no_mangle]
             pub extern "C" fn #constructor_ident(#(#inputs),*) -> *mut #ident {
+                ::crops::error::catch_panic(std::ptr::null_mut(), || {
+                    ::crops::error::into_ptr(
+                        #inner_constructor(#(#constructor),*)
+                            .map_err(|e| e.context(stringify!(#ident)))
+                    )
+                })
+            }
+
+            #(#filtered_attrs)*
+            /// ------
+            /// Unique Constructor with sepcific fields, writing the new model into `out`
+            /// ------
+            #[::crops::c_result_fn]
+            fn #try_constructor_ident(#(#inputs,)* out: *mut *mut #ident) -> ::crops::utils::CResult {
+                let out = ::crops::utils::check_null(out)?;
                 let res = #inner_constructor(#(#constructor),*)
-                    .expect(&format!("Error creating: {:?}", stringify!(#ident)));
+                    .map_err(|e| e.context(stringify!(#ident)))?;
 
-                Box::into_raw(Box::new(res))
+                *out = Box::into_raw(Box::new(res));
+
+                Ok(())
             }
         )
     });
@@ -731,12 +3027,110 @@ fn derive_c_builder_struct(ident: Ident, attrs: Vec<Attribute>, s: DataStruct) -
     .into()
 }
 
-fn derive_c_builder_enum(ident: Ident, attrs: Vec<Attribute>, s: DataEnum) -> TokenStream {
+/// Fieldless `repr(C)` enums marked `int_enum` are exported as C enums, and passed by value
+fn derive_c_builder_c_enum(
+    ident: Ident,
+    args: CBuilderArgs,
+    filtered_attrs: Vec<Attribute>,
+    variants: Punctuated<Variant, syn::Token![,]>,
+) -> TokenStream {
+    let var_idents = variants.iter().map(|variant| &variant.ident).collect_vec();
+    let Some(first) = var_idents.first() else {
+        panic!("C enums must have at least one variant");
+    };
+
+    // An all zero value may not be a valid discriminant, so panics return the first variant
+    let by_value = derive_c_builder_by_value(
+        ident.clone(),
+        args,
+        filtered_attrs.clone(),
+        Fields::Unit,
+        Some(quote::quote! {
+            fn zeroed() -> Self {
+                #ident::#first
+            }
+        }),
+    );
+
+    let from_int_ident = syn::Ident::new(
+        &format!("{}_from_int", ident.to_string().to_case(Case::Snake)),
+        ident.span(),
+    );
+    let to_int_ident = syn::Ident::new(
+        &format!("{}_to_int", ident.to_string().to_case(Case::Snake)),
+        ident.span(),
+    );
+
+    quote::quote! {
+        #by_value
+
+        impl #ident {
+            #[doc(hidden)]
+            pub const CROPS_INT_ENUM: bool = true;
+        }
+
+        impl ::crops::traits::IntEnum for #ident {
+            fn from_int(value: i32) -> Result<Self, ::crops::error::Error> {
+                [#(#ident::#var_idents),*]
+                    .into_iter()
+                    .find(|variant| *variant as i32 == value)
+                    .ok_or_else(|| {
+                        ::crops::error::Error::invalid_discriminant(value).context(stringify!(#ident))
+                    })
+            }
+        }
+
+        #(#filtered_attrs)*
+        /// ------
+        /// Converts an integer into the variant with that discriminant, returns an error if there is none.
+        /// ------
+        #[::crops::c_result_fn]
+        fn #from_int_ident(value: i32, c_value: *mut #ident) -> ::crops::utils::CResult {
+            let variant = <#ident as ::crops::traits::IntEnum>::from_int(value)?;
+
+            *::crops::utils::check_null(c_value)? = variant;
+
+            Ok(())
+        }
+
+        #(#filtered_attrs)*
+        /// ------
+        /// Converts a variant into its discriminant, returns an error if no variant has that discriminant.
+        /// ------
+        #[::crops::c_result_fn]
+        fn #to_int_ident(value: i32, c_value: *mut i32) -> ::crops::utils::CResult {
+            let variant = <#ident as ::crops::traits::IntEnum>::from_int(value)?;
+
+            *::crops::utils::check_null(c_value)? = variant as i32;
+
+            Ok(())
+        }
+    }
+    .into()
+}
+
+fn derive_c_builder_enum(
+    ident: Ident,
+    vis: Visibility,
+    attrs: Vec<Attribute>,
+    s: DataEnum,
+) -> TokenStream {
     let DataEnum { variants, .. } = s;
 
     let mut args = CBuilderArgs::default();
+    let repr_c = has_repr(&attrs, "C");
     let filtered_attrs = args.extract(attrs);
 
+    if args.c_int_enum {
+        if !repr_c {
+            panic!("C enums must be #[repr(C)]");
+        }
+        if variants.iter().any(|variant| !variant.fields.is_empty()) {
+            panic!("C enums must be fieldless");
+        }
+        return derive_c_builder_c_enum(ident, args, filtered_attrs, variants);
+    }
+
     let c_default = args.c_new.then(|| {
         let new_ident = syn::Ident::new(
             &format!("{}_default", ident.to_string().to_case(Case::Snake)),
@@ -749,7 +3143,7 @@ fn derive_c_builder_enum(ident: Ident, attrs: Vec<Attribute>, s: DataEnum) -> To
             /// ------
             #[no_mangle]
             pub extern "C" fn #new_ident() -> *mut #ident {
-                Box::into_raw(Box::default())
+                ::crops::error::catch_panic(std::ptr::null_mut(), || Box::into_raw(Box::default()))
             }
         )
     });
@@ -766,7 +3160,7 @@ fn derive_c_builder_enum(ident: Ident, attrs: Vec<Attribute>, s: DataEnum) -> To
             /// ------
             #[no_mangle]
             pub extern "C" fn #clone_ident(s: &#ident) -> *mut #ident {
-                Box::into_raw(Box::new(s.clone()))
+                ::crops::error::catch_panic(std::ptr::null_mut(), || Box::into_raw(Box::new(s.clone())))
             }
         )
     });
@@ -784,12 +3178,13 @@ fn derive_c_builder_enum(ident: Ident, attrs: Vec<Attribute>, s: DataEnum) -> To
             /// ------
             #[no_mangle]
             pub extern "C" fn #debug_ident(s: &#ident) {
-                println!("{:?}", s);
+                ::crops::error::catch_panic((), || println!("{:?}", s))
             }
         )
     });
 
-    let c_free = args.c_debug.then(|| {
+    // Opaque types can always be freed, even if they can't be debugged
+    let c_free = {
         let free_ident = syn::Ident::new(
             &format!("{}_free", ident.to_string().to_case(Case::Snake)),
             ident.span(),
@@ -806,10 +3201,46 @@ fn derive_c_builder_enum(ident: Ident, attrs: Vec<Attribute>, s: DataEnum) -> To
             /// ------
             #[no_mangle]
             pub unsafe extern "C" fn #free_ident(s: *mut #ident) {
-                ::crops::c_free!(s);
+                ::crops::error::catch_panic((), || ::crops::c_free!(s))
             }
         )
-    });
+    };
+
+    let tag_ident = format_ident!("{ident}Tag");
+    let var_idents = variants.iter().map(|variant| &variant.ident).collect_vec();
+
+    let tag_fn_ident = syn::Ident::new(
+        &format!("{}_tag", ident.to_string().to_case(Case::Snake)),
+        ident.span(),
+    );
+
+    let c_tag = quote::quote!(
+        /// ------
+        /// The variants of the enum, without their data
+        /// ------
+        #[repr(C)]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #vis enum #tag_ident {
+            #(#var_idents),*
+        }
+
+        #(#filtered_attrs)*
+        /// ------
+        /// Gets the variant that the enum currently holds
+        /// ------
+        #[::crops::c_result_fn]
+        fn #tag_fn_ident(source: *const #ident, c_value: *mut #tag_ident) -> ::crops::utils::CResult {
+            let tag = match ::crops::utils::check_null_const(source)
+                .map_err(|e| e.context(stringify!(#ident)))?
+            {
+                #(#ident::#var_idents { .. } => #tag_ident::#var_idents),*
+            };
+
+            *::crops::utils::check_null(c_value)? = tag;
+
+            Ok(())
+        }
+    );
 
     let variants = variants.iter().map(|variant| {
         let Variant {
@@ -821,34 +3252,105 @@ fn derive_c_builder_enum(ident: Ident, attrs: Vec<Attribute>, s: DataEnum) -> To
 
         let (_, filtered_attrs) = filter_args(attrs);
 
-        let (input_args, enum_filler): (Vec<_>, _) = if let Fields::Unnamed(unnamed) = &fields {
-            if unnamed.unnamed.len() != 1 {
-                panic!("Only single unnamed fields supported");
-            }
+        let fields = fields
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let (name, member) = field_name(idx, field);
+                // A lone unnamed field is simply the variant's `value`
+                let arg = match (&member, fields.len()) {
+                    (Member::Named(_), _) => name.clone(),
+                    (Member::Unnamed(_), 1) => format_ident!("value"),
+                    (Member::Unnamed(_), _) => {
+                        format_ident!("value_{}", name.to_string().to_case(Case::Snake))
+                    }
+                };
+                (name, member, arg, field)
+            })
+            .collect_vec();
 
-            let field = unnamed.unnamed.first().unwrap();
+        let (input_args, fillers): (Vec<_>, Vec<_>) = fields
+            .iter()
+            .map(|(_, member, arg, field)| {
+                let CTypes {
+                    from_c,
+                    from_c_parser,
+                    ..
+                } = gen_c_types(field);
+                let parser = from_c_parser(arg);
+
+                (
+                    quote::quote!(#arg: #from_c),
+                    quote::quote! {
+                        #member: {
+                            let inner #parser;
+                            inner
+                        }
+                    },
+                )
+            })
+            .unzip();
 
-            let CTypes {
-                from_c,
-                from_c_parser,
-                ..
-            } = gen_c_types(field);
-            let parser = from_c_parser(&parse_quote!(value));
+        let enum_filler = (!fields.is_empty()).then(|| quote::quote!({ #(#fillers),* }));
+        let checks = fields
+            .iter()
+            .filter_map(|(name, _, _, field)| field_int_enum_check(var_ident, name, field));
 
-            (
-                vec![quote::quote!(value: #from_c)],
-                quote::quote! {
-                    ({
-                        let inner #parser;
-                        inner
-                    })
-                }
-            )
-        } else if matches!(fields, Fields::Unit) {
-            (Default::default(), Default::default())
-        } else {
-            panic!("Please use a single struct as your enum data")
-        };
+        let getters = fields
+            .iter()
+            .map(|(name, member, _, field)| {
+                let (_, filtered_attrs) = filter_args(&field.attrs);
+
+                let CTypes {
+                    to_c,
+                    to_c_extra,
+                    to_c_parser,
+                    ..
+                } = gen_c_types(field);
+                let unparser = to_c_parser(&format_ident!("c_value"), &format_ident!("value"));
+
+                // A lone unnamed field is the variant's payload, and is retrieved by the variant name
+                let getter_ident = match (member, fields.len()) {
+                    (Member::Unnamed(_), 1) => syn::Ident::new(
+                        &format!(
+                            "{}_get_{}",
+                            ident.to_string().to_case(Case::Snake),
+                            var_ident.to_string().to_case(Case::Snake),
+                        ),
+                        var_ident.span(),
+                    ),
+                    _ => syn::Ident::new(
+                        &format!(
+                            "{}_get_{}_{}",
+                            ident.to_string().to_case(Case::Snake),
+                            var_ident.to_string().to_case(Case::Snake),
+                            name.to_string().to_case(Case::Snake)
+                        ),
+                        name.span(),
+                    ),
+                };
+
+                quote::quote!(
+                    #(#filtered_attrs)*
+                    /// ------
+                    /// Gets the current value of the field, returns an error if the enum holds a different variant
+                    /// ------
+                    #[::crops::c_result_fn]
+                    fn #getter_ident(source: *const #ident, c_value: #to_c #to_c_extra) -> ::crops::utils::CResult {
+                        match ::crops::utils::check_null_const(source)
+                            .map_err(|e| e.context(stringify!(#ident)))?
+                        {
+                            #ident::#var_ident { #member: value, .. } => {
+                                #unparser;
+
+                                Ok(())
+                            }
+                            _ => Err(::crops::error::Error::variant_mismatch(stringify!(#var_ident))
+                                .context(stringify!(#ident))),
+                        }
+                    }
+                )
+            });
 
         let as_variant_ident = syn::Ident::new(
             &format!(
@@ -860,6 +3362,8 @@ fn derive_c_builder_enum(ident: Ident, attrs: Vec<Attribute>, s: DataEnum) -> To
         );
 
         quote::quote!(
+            #(#checks)*
+
             #(#filtered_attrs)*
             /// ------
             /// Convert the enum into a new variant type
@@ -867,11 +3371,13 @@ fn derive_c_builder_enum(ident: Ident, attrs: Vec<Attribute>, s: DataEnum) -> To
             #[::crops::c_result_fn]
             pub fn #as_variant_ident(res: *mut #ident #(, #input_args)*) -> ::crops::utils::CResult {
                 let res = ::crops::utils::check_null(res)
-                    .map_err(|e| format!("{e} ({})", stringify!(#ident)))?;
+                    .map_err(|e| e.context(stringify!(#ident)))?;
                 *res = #ident::#var_ident #enum_filler;
 
                 Ok(())
             }
+
+            #(#getters)*
         )
     });
 
@@ -884,6 +3390,8 @@ fn derive_c_builder_enum(ident: Ident, attrs: Vec<Attribute>, s: DataEnum) -> To
 
         #c_free
 
+        #c_tag
+
         #(#variants)*
 
     }
@@ -0,0 +1,88 @@
+//! C callbacks are passed as a function pointer, a `void *user_data` handed back to every call, and an
+//! optional destructor for the user data. The generated code wraps them into Rust closures.
+
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Releases the user data of a callback once Rust no longer holds the callback
+pub type Destructor = Option<extern "C" fn(*mut c_void)>;
+
+/// The user data of a C callback, owned by the Rust closure wrapping it.
+///
+/// The destructor, if any, is called exactly once when this is dropped.
+pub struct UserData {
+    user_data: *mut c_void,
+    destructor: Destructor,
+    calling: AtomicBool,
+}
+
+impl UserData {
+    pub fn new(user_data: *mut c_void, destructor: Destructor) -> Self {
+        Self {
+            user_data,
+            destructor,
+            calling: AtomicBool::new(false),
+        }
+    }
+
+    /// Invoke a callback which may be re-entered, such as a `Fn` or a `&self` method
+    pub fn call<R>(&self, f: impl FnOnce(*mut c_void) -> R) -> R {
+        f(self.user_data)
+    }
+
+    /// Invoke a callback which must not be re-entered, such as a `FnMut` or a `&mut self` method.
+    ///
+    /// A callback which re-enters Rust and triggers itself again panics instead, which is caught
+    /// by the generated function C called into, rather than aliasing the running callback.
+    pub fn call_exclusive<R>(&self, f: impl FnOnce(*mut c_void) -> R) -> R {
+        struct Reset<'a>(&'a AtomicBool);
+
+        impl Drop for Reset<'_> {
+            fn drop(&mut self) {
+                self.0.store(false, Ordering::Release);
+            }
+        }
+
+        if self.calling.swap(true, Ordering::Acquire) {
+            panic!("Callback re-entered while it was already running");
+        }
+        let _reset = Reset(&self.calling);
+
+        f(self.user_data)
+    }
+}
+
+impl Drop for UserData {
+    fn drop(&mut self) {
+        if let Some(destructor) = self.destructor {
+            destructor(self.user_data);
+        }
+    }
+}
+
+/// Marks a value received from C as safe to send and share between threads.
+///
+/// Wraps the user data of callbacks marked `#[c_builder(c_thread_safe)]`, whose C implementation
+/// promises to be safe to call from any thread.
+pub struct ThreadSafe<T>(T);
+
+impl<T> ThreadSafe<T> {
+    /// # Safety
+    ///
+    /// The value must be safe to use from any thread, and from several threads at once.
+    pub unsafe fn new(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T> std::ops::Deref for ThreadSafe<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+// Safety: promised by the caller of `ThreadSafe::new`
+unsafe impl<T> Send for ThreadSafe<T> {}
+unsafe impl<T> Sync for ThreadSafe<T> {}
@@ -0,0 +1,278 @@
+use std::cell::RefCell;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// The first error code available to user errors, see `#[derive(CError)]`.
+///
+/// All codes below this value are reserved for [`CropsErrorCode`].
+pub const CROPS_USER_ERROR_START: i32 = 100;
+
+/// Error codes returned by the generated FFI for failures detected by crops itself
+///
+/// cbindgen:prefix-with-name
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CropsErrorCode {
+    Ok = 0,
+    Unknown = 1,
+    NullPointer = 2,
+    IndexOutOfRange = 3,
+    OptionEmpty = 4,
+    InvalidUtf8 = 5,
+    Panic = 6,
+    BufferTooSmall = 7,
+    VariantMismatch = 8,
+    KeyNotFound = 9,
+    InvalidChar = 10,
+    InvalidDiscriminant = 11,
+}
+
+/// An error that can be reported across the FFI boundary as an integer code
+pub trait CError: std::fmt::Display {
+    fn code(&self) -> i32;
+}
+
+impl CError for String {
+    fn code(&self) -> i32 {
+        CropsErrorCode::Unknown as i32
+    }
+}
+
+/// The error type used by all crops generated functions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    code: CropsErrorCode,
+    message: String,
+}
+
+impl Error {
+    pub fn new(code: CropsErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn null_pointer() -> Self {
+        Self::new(CropsErrorCode::NullPointer, "Null Pointer Received by Rust")
+    }
+
+    pub fn index_out_of_range() -> Self {
+        Self::new(CropsErrorCode::IndexOutOfRange, "Index Out of Range")
+    }
+
+    pub fn option_empty() -> Self {
+        Self::new(CropsErrorCode::OptionEmpty, "Option Empty")
+    }
+
+    pub fn invalid_utf8(e: impl std::fmt::Debug) -> Self {
+        Self::new(CropsErrorCode::InvalidUtf8, format!("{e:?}"))
+    }
+
+    pub fn buffer_too_small(required: usize, capacity: usize) -> Self {
+        Self::new(
+            CropsErrorCode::BufferTooSmall,
+            format!("Buffer Too Small: {required} required, {capacity} available"),
+        )
+    }
+
+    pub fn invalid_char(value: u32) -> Self {
+        Self::new(
+            CropsErrorCode::InvalidChar,
+            format!("Invalid Char: {value:#x} is not a unicode scalar value"),
+        )
+    }
+
+    pub fn invalid_discriminant(value: i32) -> Self {
+        Self::new(
+            CropsErrorCode::InvalidDiscriminant,
+            format!("Invalid Discriminant: {value}"),
+        )
+    }
+
+    pub fn key_not_found() -> Self {
+        Self::new(CropsErrorCode::KeyNotFound, "Key Not Found")
+    }
+
+    pub fn variant_mismatch(expected: &str) -> Self {
+        Self::new(
+            CropsErrorCode::VariantMismatch,
+            format!("Variant Mismatch: expected {expected}"),
+        )
+    }
+
+    /// Annotate the error message with the context it occured in, e.g. the type name
+    pub fn context(self, context: &str) -> Self {
+        Self {
+            message: format!("{} ({context})", self.message),
+            ..self
+        }
+    }
+
+    pub fn kind(&self) -> CropsErrorCode {
+        self.code
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Self::new(CropsErrorCode::Unknown, message)
+    }
+}
+
+impl CError for Error {
+    fn code(&self) -> i32 {
+        self.code as i32
+    }
+}
+
+/// The error of a generated function wrapping user code, which can fail either in the checks and
+/// conversions crops makes around the call, or in the user code itself
+#[derive(Debug)]
+pub enum WrappedError<E> {
+    Crops(Error),
+    User(E),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for WrappedError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Crops(e) => e.fmt(f),
+            Self::User(e) => e.fmt(f),
+        }
+    }
+}
+
+impl<E> From<Error> for WrappedError<E> {
+    fn from(e: Error) -> Self {
+        Self::Crops(e)
+    }
+}
+
+impl<E: CError> CError for WrappedError<E> {
+    fn code(&self) -> i32 {
+        match self {
+            Self::Crops(e) => e.code(),
+            Self::User(e) => e.code(),
+        }
+    }
+}
+
+/// Convert the result of a generated function into its error code, recording any error message
+pub fn into_code<E: CError>(res: Result<(), E>) -> i32 {
+    match res {
+        Ok(()) => CropsErrorCode::Ok as i32,
+        Err(e) => {
+            update_last_error(&e);
+            e.code()
+        }
+    }
+}
+
+/// Convert the result of a generated constructor into an owned pointer, recording any error
+/// message and returning a null pointer in its place
+pub fn into_ptr<T, E: CError>(res: Result<T, E>) -> *mut T {
+    or_null(res.map(|value| Box::into_raw(Box::new(value))))
+}
+
+/// Unwrap the result of a generated function returning a pointer, recording any error message
+/// and returning a null pointer in its place
+pub fn or_null<T, E: CError>(res: Result<*mut T, E>) -> *mut T {
+    match res {
+        Ok(ptr) => ptr,
+        Err(e) => {
+            update_last_error(&e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Record an error message as the most recent error on the current thread
+pub fn update_last_error(message: impl ToString) {
+    LAST_ERROR.with(|last| {
+        last.borrow_mut().replace(message.to_string());
+    });
+}
+
+/// Retrieve the most recent error on the current thread, clearing it
+pub fn take_last_error() -> Option<String> {
+    LAST_ERROR.with(|last| last.borrow_mut().take())
+}
+
+/// Retrieve a copy of the most recent error on the current thread
+pub fn last_error() -> Option<String> {
+    LAST_ERROR.with(|last| last.borrow().clone())
+}
+
+/// Run `f`, catching any panic so that it never unwinds across the FFI boundary.
+///
+/// A caught panic is recorded as the most recent error, and `on_panic` is returned in its place.
+pub fn catch_panic<T>(on_panic: T, f: impl FnOnce() -> T) -> T {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+        Ok(res) => res,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "Unknown panic payload".into());
+
+            update_last_error(format!("Rust panicked: {message}"));
+            on_panic
+        }
+    }
+}
+
+/// Length of the most recent error message, including the trailing NUL byte.
+///
+/// Returns 0 if there is no error recorded on the current thread.
+#[no_mangle]
+pub extern "C" fn crops_last_error_length() -> usize {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map(|message| message.len() + 1)
+            .unwrap_or(0)
+    })
+}
+
+/// Copy the most recent error message into the provided buffer, NUL terminated.
+///
+/// Returns the number of bytes written (excluding the NUL byte), 0 if there is no error, or -1 if
+/// the buffer is null or too small to hold the message. The error is not cleared.
+///
+/// # Safety
+///
+/// `buffer` must either be null or point to at least `length` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn crops_last_error_message(buffer: *mut libc::c_char, length: usize) -> i32 {
+    let Some(message) = last_error() else {
+        return 0;
+    };
+
+    if buffer.is_null() || length < message.len() + 1 {
+        return -1;
+    }
+
+    let bytes = std::slice::from_raw_parts_mut(buffer as *mut u8, length);
+    bytes[..message.len()].copy_from_slice(message.as_bytes());
+    bytes[message.len()] = 0;
+
+    message.len() as i32
+}
+
+/// Clear the most recent error on the current thread
+#[no_mangle]
+pub extern "C" fn crops_last_error_clear() {
+    take_last_error();
+}
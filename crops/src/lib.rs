@@ -1,3 +1,5 @@
+pub mod callback;
+pub mod error;
 pub mod utils;
 pub use crops_derive::*;
 
@@ -3,4 +3,28 @@ fn trybuild() {
     let t = trybuild::TestCases::new();
     t.pass("tests/enum.rs");
     t.pass("tests/struct.rs");
+    t.pass("tests/error.rs");
+    t.pass("tests/c_error.rs");
+    t.pass("tests/panic.rs");
+    t.pass("tests/constructor.rs");
+    t.pass("tests/string.rs");
+    t.pass("tests/owned_string.rs");
+    t.pass("tests/bytes.rs");
+    t.pass("tests/tuple.rs");
+    t.pass("tests/enum_fields.rs");
+    t.pass("tests/generic.rs");
+    t.pass("tests/map.rs");
+    t.pass("tests/set.rs");
+    t.pass("tests/vec.rs");
+    t.pass("tests/nested.rs");
+    t.pass("tests/array.rs");
+    t.pass("tests/integers.rs");
+    t.pass("tests/pod.rs");
+    t.pass("tests/c_enum.rs");
+    t.pass("tests/callback.rs");
+    t.pass("tests/c_trait.rs");
+    t.pass("tests/c_impl.rs");
+    t.pass("tests/out.rs");
+    t.pass("tests/args.rs");
+    t.pass("tests/take.rs");
 }
@@ -17,3 +17,48 @@ impl<T> AsConstPtr<T> for T {
         self as *const T
     }
 }
+
+/// Types that can be passed by value across the FFI boundary, rather than behind a pointer.
+///
+/// # Safety
+///
+/// The type must have a C compatible layout, and a value of all zero bytes must be valid for it,
+/// unless [`ByValue::zeroed`] is overridden.
+pub unsafe trait ByValue: Copy {
+    /// The value returned in place of a by value result when Rust panics
+    fn zeroed() -> Self {
+        // Safety: guaranteed by the implementor
+        unsafe { std::mem::zeroed() }
+    }
+}
+
+macro_rules! by_value {
+    ($($t:ty),*) => {
+        $(unsafe impl ByValue for $t {})*
+    };
+}
+
+by_value!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64, bool);
+
+/// Fieldless enums exported as C enums, which C passes to Rust as an `int` so that it can be checked
+pub trait IntEnum: ByValue {
+    /// Convert an integer into the variant with that discriminant, if there is one
+    fn from_int(value: i32) -> Result<Self, crate::error::Error>;
+}
+
+/// Implemented for every type, so that `<T>::CROPS_BY_VALUE`, `<T>::CROPS_INT_ENUM` and
+/// `<T>::CROPS_BUILDER` are `false` unless they are shadowed by the inherent constants generated
+/// for CBuilder types
+#[doc(hidden)]
+pub trait MaybeByValue {
+    const CROPS_BY_VALUE: bool = false;
+    const CROPS_INT_ENUM: bool = false;
+    const CROPS_BUILDER: bool = false;
+}
+
+impl<T: ?Sized> MaybeByValue for T {}
+
+/// Identity function, used by generated code to assert that a value is [`ByValue`]
+pub fn by_value<T: ByValue>(value: T) -> T {
+    value
+}
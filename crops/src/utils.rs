@@ -1,44 +1,140 @@
-pub type CResult = Result<(), String>;
+// These helpers are the boundary where raw pointers received over FFI get checked, so they
+// intentionally take raw pointers from safe code.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
 
-pub fn check_null<'a, T>(value: *mut T) -> Result<&'a mut T, String> {
+use crate::error::Error;
+
+pub type CResult<E = Error> = Result<(), E>;
+
+pub fn check_null<'a, T>(value: *mut T) -> Result<&'a mut T, Error> {
     if value.is_null() {
-        Err("Null Pointed Received by Rust".into())
+        Err(Error::null_pointer())
     } else {
         Ok(unsafe { &mut *value })
     }
 }
 
-pub fn check_null_const<'a, T>(value: *const T) -> Result<&'a T, String> {
+pub fn check_null_const<'a, T>(value: *const T) -> Result<&'a T, Error> {
     if value.is_null() {
-        Err("Null Pointed Received by Rust".into())
+        Err(Error::null_pointer())
     } else {
         Ok(unsafe { &*value })
     }
 }
 
-pub fn as_string(c_str: *const libc::c_char) -> Result<String, String> {
+pub fn as_string(c_str: *const libc::c_char) -> Result<String, Error> {
+    check_null_const(c_str)?;
+
     let res = unsafe { std::ffi::CStr::from_ptr(c_str) }
         .to_str()
-        .map_err(|e| format!("{e:?}"))
+        .map_err(Error::invalid_utf8)
         .map(|s| s.to_string());
 
     res
 }
 
-pub fn copy_string(c_value: *mut libc::c_char, value: &str) -> Result<(), String> {
-    let res = std::ffi::CString::new(value)
-        .map(|s| s.as_bytes_with_nul().to_vec())
-        .map_err(|e| format!("{e:?}"))?;
+/// Copy a string into a caller provided buffer of `capacity` bytes, always NUL terminating it.
+///
+/// If `length` is not null, the length of the string (excluding the NUL terminator) is written to
+/// it, even when the buffer is too small. Passing a null buffer only queries the length.
+///
+/// Like `snprintf`, a buffer which is too small is still filled with as much of the string as fits
+/// before the NUL terminator, cut at a character boundary, and an error is returned.
+pub fn copy_string(
+    c_value: *mut libc::c_char,
+    capacity: usize,
+    length: *mut usize,
+    value: &str,
+) -> Result<(), Error> {
+    let res = std::ffi::CString::new(value).map_err(|e| Error::from(format!("{e:?}")))?;
+    let res = res.as_bytes_with_nul();
 
-    let res = &*(res.as_slice());
+    if c_value.is_null() {
+        *check_null(length)? = value.len();
+        return Ok(());
+    }
 
-    let bytes = unsafe { std::slice::from_raw_parts_mut(c_value as *mut u8, value.len()) };
+    if !length.is_null() {
+        unsafe { *length = value.len() };
+    }
+
+    if capacity < res.len() {
+        if capacity > 0 {
+            let mut end = capacity - 1;
+            while !value.is_char_boundary(end) {
+                end -= 1;
+            }
 
-    bytes[..value.len()].copy_from_slice(&res[..value.len()]);
+            let bytes = unsafe { std::slice::from_raw_parts_mut(c_value as *mut u8, end + 1) };
+            bytes[..end].copy_from_slice(&res[..end]);
+            bytes[end] = 0;
+        }
+
+        return Err(Error::buffer_too_small(res.len(), capacity));
+    }
+
+    let bytes = unsafe { std::slice::from_raw_parts_mut(c_value as *mut u8, res.len()) };
+
+    bytes.copy_from_slice(res);
 
     Ok(())
 }
 
+/// Borrow `len` elements starting at `ptr` as a slice, a null pointer is only permitted when `len` is 0
+pub fn as_slice<'a, T>(ptr: *const T, len: usize) -> Result<&'a [T], Error> {
+    if len == 0 {
+        Ok(&[])
+    } else {
+        check_null_const(ptr)?;
+        Ok(unsafe { std::slice::from_raw_parts(ptr, len) })
+    }
+}
+
+/// Copy a slice into a caller provided buffer of `capacity` elements
+pub fn copy_slice<T: Copy>(c_value: *mut T, capacity: usize, value: &[T]) -> Result<(), Error> {
+    if capacity < value.len() {
+        return Err(Error::buffer_too_small(value.len(), capacity));
+    }
+
+    if !value.is_empty() {
+        check_null(c_value)?;
+        let c_value = unsafe { std::slice::from_raw_parts_mut(c_value, value.len()) };
+        c_value.copy_from_slice(value);
+    }
+
+    Ok(())
+}
+
+/// Copy a string into a newly allocated C string, which must be released with `crops_string_free`
+pub fn into_c_string(value: &str) -> Result<*mut libc::c_char, Error> {
+    std::ffi::CString::new(value)
+        .map(std::ffi::CString::into_raw)
+        .map_err(|e| Error::from(format!("{e:?}")))
+}
+
+/// Convert a code point received from C into a `char`, rejecting surrogates and out of range values
+pub fn as_char(value: u32) -> Result<char, Error> {
+    char::from_u32(value).ok_or_else(|| Error::invalid_char(value))
+}
+
+/// Move a value out of a box allocated by Rust, freeing the box
+pub fn take_box<T>(value: *mut T) -> Result<T, Error> {
+    check_null(value)?;
+    Ok(*unsafe { Box::from_raw(value) })
+}
+
+/// Free a string allocated by Rust.
+///
+/// # Safety
+///
+/// The provided pointer must have been returned by one of the crops `get_owned` functions, this function will free that memory
+#[no_mangle]
+pub unsafe extern "C" fn crops_string_free(s: *mut libc::c_char) {
+    if !s.is_null() {
+        crate::error::catch_panic((), || drop(std::ffi::CString::from_raw(s)));
+    }
+}
+
 pub mod duration {
 
     #[derive(Clone, Debug, Default)]
@@ -72,3 +168,56 @@ pub mod duration {
         crate::c_free!(d);
     }
 }
+
+pub mod int128 {
+    //! 128 bit integers have no stable C ABI, so they cross the boundary split into two halves
+
+    /// An unsigned 128 bit integer, `hi` holds the upper 64 bits
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct CropsU128 {
+        pub hi: u64,
+        pub lo: u64,
+    }
+
+    /// A signed 128 bit integer, `hi` holds the upper 64 bits including the sign
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct CropsI128 {
+        pub hi: i64,
+        pub lo: u64,
+    }
+
+    impl From<u128> for CropsU128 {
+        fn from(value: u128) -> Self {
+            Self {
+                hi: (value >> 64) as u64,
+                lo: value as u64,
+            }
+        }
+    }
+
+    impl From<CropsU128> for u128 {
+        fn from(value: CropsU128) -> Self {
+            ((value.hi as u128) << 64) | value.lo as u128
+        }
+    }
+
+    impl From<i128> for CropsI128 {
+        fn from(value: i128) -> Self {
+            Self {
+                hi: (value >> 64) as i64,
+                lo: value as u64,
+            }
+        }
+    }
+
+    impl From<CropsI128> for i128 {
+        fn from(value: CropsI128) -> Self {
+            ((value.hi as i128) << 64) | value.lo as i128
+        }
+    }
+
+    unsafe impl crate::traits::ByValue for CropsU128 {}
+    unsafe impl crate::traits::ByValue for CropsI128 {}
+}
@@ -0,0 +1,126 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Inventory {
+    items: Vec<String>,
+}
+
+#[crops::c_result_fn]
+fn inventory_add(inventory: &mut Inventory, name: &str, count: usize) -> crops::utils::CResult {
+    inventory
+        .items
+        .extend(std::iter::repeat_n(name.to_string(), count));
+
+    Ok(())
+}
+
+#[crops::c_result_fn]
+fn inventory_rename_all(inventory: &mut Inventory, name: String) -> crops::utils::CResult {
+    inventory
+        .items
+        .iter_mut()
+        .for_each(|item| *item = name.clone());
+
+    Ok(())
+}
+
+#[crops::c_result_fn(out)]
+fn inventory_count(
+    inventory: &Inventory,
+    prefix: Option<&str>,
+    exclude: Option<&Inventory>,
+) -> usize {
+    inventory
+        .items
+        .iter()
+        .filter(|item| prefix.is_none_or(|prefix| item.starts_with(prefix)))
+        .filter(|item| exclude.is_none_or(|exclude| !exclude.items.contains(item)))
+        .count()
+}
+
+#[crops::c_result_fn(out)]
+fn sum(values: &[i64]) -> i64 {
+    values.iter().sum()
+}
+
+pub fn main() {
+    let inventory = inventory_default();
+
+    assert_eq!(
+        inventory_add(inventory, c"apple".as_ptr(), 2),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        inventory_add(inventory, c"pear".as_ptr(), 1),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        inventory_add(inventory, std::ptr::null(), 1),
+        CropsErrorCode::NullPointer as i32
+    );
+    assert_eq!(
+        inventory_add(std::ptr::null_mut(), c"plum".as_ptr(), 1),
+        CropsErrorCode::NullPointer as i32
+    );
+    assert_eq!(
+        inventory_add(inventory, c"\xff".as_ptr(), 1),
+        CropsErrorCode::InvalidUtf8 as i32
+    );
+
+    let mut count = 0usize;
+    assert_eq!(
+        inventory_count(
+            inventory,
+            std::ptr::null(),
+            std::ptr::null(),
+            count.as_mut_ptr()
+        ),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(count, 3);
+    assert_eq!(
+        inventory_count(
+            inventory,
+            c"ap".as_ptr(),
+            std::ptr::null(),
+            count.as_mut_ptr()
+        ),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(count, 2);
+
+    let exclude = inventory_default();
+    inventory_add(exclude, c"pear".as_ptr(), 1);
+    assert_eq!(
+        inventory_count(inventory, std::ptr::null(), exclude, count.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(count, 2);
+
+    assert_eq!(
+        inventory_rename_all(inventory, c"fig".as_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*inventory }.items, vec!["fig"; 3]);
+
+    let values = [1i64, 2, 3];
+    let mut total = 0i64;
+    assert_eq!(
+        sum(values.as_ptr(), values.len(), total.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(total, 6);
+    assert_eq!(
+        sum(std::ptr::null(), 0, total.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(total, 0);
+    assert_eq!(
+        sum(std::ptr::null(), 2, total.as_mut_ptr()),
+        CropsErrorCode::NullPointer as i32
+    );
+
+    unsafe { inventory_free(exclude) };
+    unsafe { inventory_free(inventory) };
+}
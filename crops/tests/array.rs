@@ -0,0 +1,54 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Pixel {
+    rgb: [u8; 3],
+    weights: [f32; 4],
+}
+
+pub fn main() {
+    assert_eq!(PIXEL_RGB_LEN, 3);
+    assert_eq!(PIXEL_WEIGHTS_LEN, 4);
+
+    let pixel = pixel_default();
+
+    let rgb = [10u8, 20, 30];
+    assert_eq!(
+        pixel_set_rgb(pixel, rgb.as_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        pixel_set_rgb(pixel, std::ptr::null()),
+        CropsErrorCode::NullPointer as i32
+    );
+    assert_eq!(pixel_set_item_rgb(pixel, 1, 25), CropsErrorCode::Ok as i32);
+    assert_eq!(
+        pixel_set_item_rgb(pixel, 3, 25),
+        CropsErrorCode::IndexOutOfRange as i32
+    );
+
+    let mut buffer = vec![0u8; PIXEL_RGB_LEN];
+    assert_eq!(
+        pixel_get_rgb(pixel, buffer.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(buffer, vec![10, 25, 30]);
+
+    let mut weight = 0f32;
+    assert_eq!(
+        pixel_set_item_weights(pixel, 2, 0.5),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        pixel_get_item_weights(pixel, 2, weight.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(weight, 0.5);
+    assert_eq!(
+        pixel_get_item_weights(pixel, 4, weight.as_mut_ptr()),
+        CropsErrorCode::IndexOutOfRange as i32
+    );
+
+    unsafe { pixel_free(pixel) };
+}
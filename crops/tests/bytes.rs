@@ -0,0 +1,97 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+type Checksum = Vec<u8>;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Packet {
+    payload: Vec<u8>,
+    #[c_builder(c_bytes)]
+    checksum: Checksum,
+    #[c_builder(c_bytes)]
+    header: Box<[u8]>,
+}
+
+pub fn main() {
+    let packet = packet_default();
+    let payload = (0..=255u8).cycle().take(4096).collect::<Vec<_>>();
+
+    assert_eq!(
+        packet_set_payload(packet, payload.as_ptr(), payload.len()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*packet }.payload, payload);
+
+    let mut length = 0usize;
+    assert_eq!(
+        packet_get_payload_len(packet, length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(length, payload.len());
+
+    let mut buffer = vec![0u8; length - 1];
+    assert_eq!(
+        packet_copy_payload(packet, buffer.as_mut_ptr(), buffer.len()),
+        CropsErrorCode::BufferTooSmall as i32
+    );
+
+    let mut buffer = vec![0u8; length];
+    assert_eq!(
+        packet_copy_payload(packet, buffer.as_mut_ptr(), buffer.len()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(buffer, payload);
+
+    let mut view: *const u8 = std::ptr::null();
+    assert_eq!(
+        packet_view_payload(packet, view.as_mut_ptr(), length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { std::slice::from_raw_parts(view, length) }, payload);
+
+    assert_eq!(
+        packet_set_checksum(packet, [0xde, 0xad].as_ptr(), 2),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*packet }.checksum, vec![0xde, 0xad]);
+
+    assert_eq!(
+        packet_set_checksum(packet, std::ptr::null(), 0),
+        CropsErrorCode::Ok as i32
+    );
+    assert!(unsafe { &*packet }.checksum.is_empty());
+    assert_eq!(
+        packet_set_checksum(packet, std::ptr::null(), 1),
+        CropsErrorCode::NullPointer as i32
+    );
+
+    assert_eq!(
+        packet_set_header(packet, [1, 2, 3].as_ptr(), 3),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(&*unsafe { &*packet }.header, &[1, 2, 3]);
+
+    assert_eq!(
+        packet_get_header_len(packet, length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(length, 3);
+
+    let mut buffer = vec![0u8; 3];
+    assert_eq!(
+        packet_copy_header(packet, buffer.as_mut_ptr(), buffer.len()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(buffer, [1, 2, 3]);
+
+    assert_eq!(
+        packet_view_header(packet, view.as_mut_ptr(), length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        unsafe { std::slice::from_raw_parts(view, length) },
+        [1, 2, 3]
+    );
+
+    unsafe { packet_free(packet) };
+}
@@ -0,0 +1,105 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[c_builder(int_enum)]
+#[repr(C)]
+enum Level {
+    Low = 1,
+    #[default]
+    Medium = 5,
+    High = 10,
+}
+
+// Without `int_enum`, repr(C) enums keep being passed behind a pointer
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+#[repr(C)]
+enum Tone {
+    #[default]
+    Beep,
+    Chime,
+}
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Alarm {
+    #[c_builder(c_int_enum)]
+    level: Level,
+    #[c_builder(c_int_enum)]
+    history: Vec<Level>,
+}
+
+#[crops::c_result_fn]
+fn alarm_escalate(alarm: *mut Alarm, #[c_builder(c_int_enum)] to: Level) -> crops::utils::CResult {
+    crops::utils::check_null(alarm)?.level = to;
+
+    Ok(())
+}
+
+pub fn main() {
+    assert_eq!(level_default(), Level::Medium);
+    assert_eq!(<Level as crops::traits::ByValue>::zeroed(), Level::Low);
+
+    let mut int = 0;
+    assert_eq!(
+        level_to_int(10, int.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(int, 10);
+    assert_eq!(
+        level_to_int(3, int.as_mut_ptr()),
+        CropsErrorCode::InvalidDiscriminant as i32
+    );
+
+    let mut level = Level::Low;
+    assert_eq!(
+        level_from_int(5, level.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(level, Level::Medium);
+    assert_eq!(
+        level_from_int(2, level.as_mut_ptr()),
+        CropsErrorCode::InvalidDiscriminant as i32
+    );
+    assert_eq!(level, Level::Medium);
+
+    let alarm = alarm_default();
+    assert_eq!(
+        alarm_with_level(alarm, Level::Low as i32),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        alarm_escalate(alarm, Level::High as i32),
+        CropsErrorCode::Ok as i32
+    );
+
+    // C enums are received as an int, which is checked
+    assert_eq!(
+        alarm_with_level(alarm, 7),
+        CropsErrorCode::InvalidDiscriminant as i32
+    );
+    assert_eq!(
+        alarm_escalate(alarm, 7),
+        CropsErrorCode::InvalidDiscriminant as i32
+    );
+    assert_eq!(
+        alarm_push_history(alarm, 7),
+        CropsErrorCode::InvalidDiscriminant as i32
+    );
+    assert_eq!(
+        alarm_push_history(alarm, Level::Low as i32),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*alarm }.history, vec![Level::Low]);
+    assert_eq!(
+        alarm_get_level(alarm, level.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(level, Level::High);
+
+    unsafe { alarm_free(alarm) };
+
+    let tone: *mut Tone = tone_default();
+    assert_eq!(tone_as_chime(tone), CropsErrorCode::Ok as i32);
+    assert_eq!(unsafe { &*tone }, &Tone::Chime);
+    unsafe { tone_free(tone) };
+}
@@ -0,0 +1,115 @@
+use crops::error::{CError, CropsErrorCode};
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CError, Debug)]
+enum ParseError {
+    Empty,
+    TooLong(usize),
+    #[c_error(code = 150)]
+    Invalid {
+        position: usize,
+    },
+    #[c_error(transparent)]
+    Crops(crops::error::Error),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "Empty input"),
+            ParseError::TooLong(len) => write!(f, "Input of length {len} is too long"),
+            ParseError::Invalid { position } => write!(f, "Invalid input at {position}"),
+            ParseError::Crops(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<crops::error::Error> for ParseError {
+    fn from(value: crops::error::Error) -> Self {
+        ParseError::Crops(value)
+    }
+}
+
+#[crops::c_result_fn]
+fn parse_length(
+    value: *const crops::_macros::libc::c_char,
+    out: *mut usize,
+) -> crops::utils::CResult<ParseError> {
+    let value = crops::utils::as_string(value)?;
+
+    if value.is_empty() {
+        return Err(ParseError::Empty);
+    }
+    if value.len() > 4 {
+        return Err(ParseError::TooLong(value.len()));
+    }
+    if let Some(position) = value.find('!') {
+        return Err(ParseError::Invalid { position });
+    }
+
+    *crops::utils::check_null(out)? = value.len();
+
+    Ok(())
+}
+
+#[derive(crops::CBuilder, Debug, Clone, Default)]
+struct Brush {
+    sizes: Vec<u16>,
+    name: Option<String>,
+}
+
+pub fn main() {
+    assert_eq!(ParseErrorCode::Empty as i32, 100);
+    assert_eq!(ParseErrorCode::TooLong as i32, 101);
+    assert_eq!(ParseErrorCode::Invalid as i32, 150);
+    assert_eq!(
+        ParseError::Crops(crops::error::Error::null_pointer()).code(),
+        CropsErrorCode::NullPointer as i32
+    );
+
+    let mut out = 0usize;
+    assert_eq!(parse_length(c"abc".as_ptr(), out.as_mut_ptr()), 0);
+    assert_eq!(out, 3);
+
+    assert_eq!(
+        parse_length(c"".as_ptr(), out.as_mut_ptr()),
+        ParseErrorCode::Empty as i32
+    );
+    assert_eq!(
+        parse_length(c"abcdef".as_ptr(), out.as_mut_ptr()),
+        ParseErrorCode::TooLong as i32
+    );
+    assert_eq!(
+        crops::error::last_error().unwrap(),
+        "Input of length 6 is too long"
+    );
+    assert_eq!(
+        parse_length(c"a!".as_ptr(), out.as_mut_ptr()),
+        ParseErrorCode::Invalid as i32
+    );
+    assert_eq!(
+        parse_length(c"\xff".as_ptr(), out.as_mut_ptr()),
+        CropsErrorCode::InvalidUtf8 as i32
+    );
+    assert_eq!(
+        parse_length(std::ptr::null(), out.as_mut_ptr()),
+        CropsErrorCode::NullPointer as i32
+    );
+
+    let brush = brush_default();
+    let mut size = 0u16;
+    assert_eq!(
+        brush_get_sizes(std::ptr::null(), 0, size.as_mut_ptr()),
+        CropsErrorCode::NullPointer as i32
+    );
+    assert_eq!(
+        brush_get_sizes(brush, 0, size.as_mut_ptr()),
+        CropsErrorCode::IndexOutOfRange as i32
+    );
+    assert_eq!(
+        brush_take_name(brush, std::ptr::null_mut(), 0, std::ptr::null_mut()),
+        CropsErrorCode::OptionEmpty as i32
+    );
+
+    unsafe { brush_free(brush) };
+}
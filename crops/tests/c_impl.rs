@@ -0,0 +1,146 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Brush {
+    name: String,
+    size: u32,
+}
+
+#[derive(crops::CBuilder, Debug, Clone, Copy, Default, PartialEq)]
+#[c_builder(pod)]
+#[repr(C)]
+struct Tip {
+    width: u32,
+}
+
+#[derive(crops::CError, Debug)]
+enum BrushError {
+    TooLarge,
+}
+
+impl std::fmt::Display for BrushError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Brush too large")
+    }
+}
+
+#[crops::c_impl]
+impl Brush {
+    /// Grows the brush by `by`
+    pub fn grow(&mut self, by: u32) -> Result<u32, BrushError> {
+        if self.size + by > 10 {
+            return Err(BrushError::TooLarge);
+        }
+        self.size += by;
+        Ok(self.size)
+    }
+
+    pub fn rename(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn rename_after(&mut self, other: Option<&Brush>, suffix: Option<&str>) {
+        if let Some(other) = other {
+            self.name = format!("{}{}", other.name, suffix.unwrap_or_default());
+        }
+    }
+
+    pub fn duplicate(&self) -> Self {
+        self.clone()
+    }
+
+    #[c_builder(c_by_value)]
+    pub fn tip(&self) -> Tip {
+        Tip { width: self.size }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_large(&self) -> bool {
+        self.size > 5
+    }
+
+    pub fn max_size() -> u32 {
+        10
+    }
+
+    #[allow(dead_code)]
+    fn private(&self) {}
+}
+
+pub fn main() {
+    let brush = brush_default();
+
+    let mut size = 0u32;
+    assert_eq!(
+        brush_grow(brush, 4, size.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(size, 4);
+    assert_eq!(
+        brush_grow(brush, 8, size.as_mut_ptr()),
+        BrushErrorCode::TooLarge as i32
+    );
+    assert_eq!(
+        brush_grow(std::ptr::null_mut(), 1, size.as_mut_ptr()),
+        CropsErrorCode::NullPointer as i32
+    );
+
+    assert_eq!(
+        brush_rename(brush, unsafe { &*c"round".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    let mut buffer = vec![0; 8];
+    assert_eq!(
+        brush_label(
+            brush,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            std::ptr::null_mut()
+        ),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) }.to_str(),
+        Ok("round")
+    );
+
+    // Opaque values are boxed, like those of `c_result_fn`
+    let mut copy = std::ptr::null_mut();
+    assert_eq!(brush_duplicate(brush, &mut copy), CropsErrorCode::Ok as i32);
+    assert_eq!(unsafe { &*copy }, unsafe { &*brush });
+
+    assert_eq!(
+        brush_rename_after(copy, brush, c" 2".as_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*copy }.name, "round 2");
+    assert_eq!(
+        brush_rename_after(copy, std::ptr::null(), std::ptr::null()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*copy }.name, "round 2");
+    unsafe { brush_free(copy) };
+
+    let mut tip = Tip::default();
+    assert_eq!(
+        brush_tip(brush, tip.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(tip, Tip { width: 4 });
+
+    let mut large = true;
+    assert_eq!(
+        brush_is_large(brush, large.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert!(!large);
+
+    assert_eq!(brush_max_size(size.as_mut_ptr()), CropsErrorCode::Ok as i32);
+    assert_eq!(size, 10);
+
+    unsafe { brush_free(brush) };
+}
@@ -0,0 +1,142 @@
+use std::ffi::c_void;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[crops::c_trait]
+pub trait Strategy {
+    fn score(&self, value: u32) -> f64;
+    fn reset(&mut self);
+}
+
+#[crops::c_trait(thread_safe)]
+pub trait Limit: Send {
+    fn max(&self) -> u32;
+}
+
+#[derive(crops::CBuilder, Clone, Default)]
+#[c_builder(debug = false)]
+struct Planner {
+    strategy: Option<Rc<dyn Strategy>>,
+    limit: Option<Arc<dyn Limit + Sync>>,
+}
+
+#[crops::c_result_fn]
+fn planner_score(planner: *const Planner, value: u32, c_value: *mut f64) -> crops::utils::CResult {
+    let strategy = crops::utils::check_null_const(planner)?
+        .strategy
+        .as_ref()
+        .ok_or_else(crops::error::Error::option_empty)?;
+    *crops::utils::check_null(c_value)? = strategy.score(value);
+
+    Ok(())
+}
+
+#[crops::c_result_fn]
+fn score_once(
+    mut strategy: Box<dyn Strategy>,
+    value: u32,
+    c_value: *mut f64,
+) -> crops::utils::CResult {
+    strategy.reset();
+    *crops::utils::check_null(c_value)? = strategy.score(value);
+
+    Ok(())
+}
+
+extern "C" fn scale(user_data: *mut c_void, value: u32) -> f64 {
+    let state = unsafe { &*(user_data as *const (f64, u32)) };
+    state.0 * value as f64 + state.1 as f64
+}
+
+extern "C" fn reset(user_data: *mut c_void) {
+    unsafe { &mut *(user_data as *mut (f64, u32)) }.1 = 0;
+}
+
+extern "C" fn release(user_data: *mut c_void) {
+    drop(unsafe { Box::from_raw(user_data as *mut (f64, u32)) });
+    RELEASED.store(true, std::sync::atomic::Ordering::SeqCst);
+}
+
+extern "C" fn max(user_data: *mut c_void) -> u32 {
+    unsafe { *(user_data as *const u32) }
+}
+
+static MAX: u32 = 7;
+
+static RELEASED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+pub fn main() {
+    let vtable = StrategyVTable {
+        user_data: Box::into_raw(Box::new((2.0f64, 1u32))) as *mut c_void,
+        score: Some(scale),
+        reset: Some(reset),
+        destructor: Some(release),
+    };
+
+    let planner = planner_default();
+    assert_eq!(
+        planner_set_strategy(planner, &vtable),
+        CropsErrorCode::Ok as i32
+    );
+
+    let mut score = 0f64;
+    assert_eq!(
+        planner_score(planner, 4, score.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(score, 9.0);
+
+    assert_eq!(
+        planner_set_strategy(planner, std::ptr::null()),
+        CropsErrorCode::Ok as i32
+    );
+    assert!(RELEASED.load(std::sync::atomic::Ordering::SeqCst));
+    assert_eq!(
+        planner_score(planner, 4, score.as_mut_ptr()),
+        CropsErrorCode::OptionEmpty as i32
+    );
+
+    let vtable = LimitVTable {
+        user_data: &MAX as *const u32 as *mut c_void,
+        max: Some(max),
+        destructor: None,
+    };
+    assert_eq!(
+        planner_set_limit(planner, &vtable),
+        CropsErrorCode::Ok as i32
+    );
+    let limit = unsafe { &*planner }.limit.clone().unwrap();
+    assert_eq!(std::thread::spawn(move || limit.max()).join().unwrap(), 7);
+
+    unsafe { planner_free(planner) };
+
+    let mut state = (3.0f64, 5u32);
+    let vtable = StrategyVTable {
+        user_data: state.as_mut_ptr() as *mut c_void,
+        score: Some(scale),
+        reset: None,
+        destructor: None,
+    };
+    // A missing function is rejected before any method is called
+    assert_eq!(
+        score_once(&vtable, 2, score.as_mut_ptr()),
+        CropsErrorCode::NullPointer as i32
+    );
+    assert_eq!(state.1, 5);
+    let vtable = StrategyVTable {
+        reset: Some(reset),
+        ..vtable
+    };
+    assert_eq!(
+        score_once(&vtable, 2, score.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(score, 6.0);
+    assert_eq!(
+        score_once(std::ptr::null(), 2, score.as_mut_ptr()),
+        CropsErrorCode::NullPointer as i32
+    );
+}
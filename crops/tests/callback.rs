@@ -0,0 +1,171 @@
+use std::ffi::c_void;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicI32, AtomicU32, Ordering};
+use std::sync::Arc;
+
+use crops::error::CropsErrorCode;
+
+#[derive(crops::CBuilder, Clone, Default)]
+#[c_builder(debug = false)]
+struct Task {
+    on_progress: Option<Rc<dyn Fn(u32)>>,
+    on_check: Option<Rc<dyn Fn(u32, u32) -> bool>>,
+}
+
+// Hooks which can't be cloned
+#[derive(crops::CBuilder, Default)]
+#[c_builder(clone = false, debug = false)]
+struct Batch {
+    on_finish: Option<Box<dyn FnMut(u32)>>,
+}
+
+// Hooks shared between threads
+#[derive(crops::CBuilder, Clone, Default)]
+#[c_builder(debug = false)]
+struct Job {
+    #[c_builder(c_thread_safe)]
+    on_done: Option<Arc<dyn Fn(u32) + Send + Sync>>,
+}
+
+#[crops::c_result_fn]
+fn task_run(task: *mut Task, steps: u32) -> crops::utils::CResult {
+    let task = crops::utils::check_null(task)?;
+    for step in 1..=steps {
+        if let Some(on_progress) = &task.on_progress {
+            on_progress(step);
+        }
+    }
+
+    Ok(())
+}
+
+#[crops::c_result_fn]
+fn batch_run(batch: *mut Batch, steps: u32) -> crops::utils::CResult {
+    if let Some(on_finish) = &mut crops::utils::check_null(batch)?.on_finish {
+        on_finish(steps);
+    }
+
+    Ok(())
+}
+
+#[crops::c_result_fn]
+fn run_with(steps: u32, on_step: Box<dyn Fn(u32) -> u32>) -> crops::utils::CResult {
+    let total = (0..steps).map(on_step).sum::<u32>();
+    assert_eq!(total, 6);
+
+    Ok(())
+}
+
+extern "C" fn record(user_data: *mut c_void, value: u32) {
+    unsafe { &mut *(user_data as *mut Vec<u32>) }.push(value);
+}
+
+extern "C" fn double(_: *mut c_void, value: u32) -> u32 {
+    value * 2
+}
+
+extern "C" fn greater(_: *mut c_void, a: u32, b: u32) -> bool {
+    a > b
+}
+
+extern "C" fn count(user_data: *mut c_void, value: u32) {
+    unsafe { &*(user_data as *const AtomicU32) }.fetch_add(value, Ordering::SeqCst);
+}
+
+static COUNT: AtomicU32 = AtomicU32::new(0);
+
+static DEPTH: AtomicU32 = AtomicU32::new(0);
+static REENTERED: AtomicI32 = AtomicI32::new(-1);
+
+extern "C" fn reenter(user_data: *mut c_void, _: u32) {
+    if DEPTH.fetch_add(1, Ordering::SeqCst) == 0 {
+        REENTERED.store(task_run(user_data as *mut Task, 1), Ordering::SeqCst);
+    }
+}
+
+extern "C" fn reenter_batch(user_data: *mut c_void, _: u32) {
+    if DEPTH.fetch_add(1, Ordering::SeqCst) == 0 {
+        REENTERED.store(batch_run(user_data as *mut Batch, 1), Ordering::SeqCst);
+    }
+}
+
+extern "C" fn release(user_data: *mut c_void) {
+    unsafe { &mut *(user_data as *mut Vec<u32>) }.push(0);
+}
+
+pub fn main() {
+    let task = task_default();
+    let mut seen = Vec::<u32>::new();
+    let seen_ptr = &mut seen as *mut Vec<u32> as *mut c_void;
+
+    assert_eq!(
+        task_set_on_progress(task, Some(record), seen_ptr, Some(release)),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(task_run(task, 3), CropsErrorCode::Ok as i32);
+
+    // Removing the callback releases its user data
+    assert_eq!(
+        task_set_on_progress(task, None, std::ptr::null_mut(), None),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(seen, vec![1, 2, 3, 0]);
+
+    assert_eq!(
+        task_set_on_check(task, Some(greater), std::ptr::null_mut(), None),
+        CropsErrorCode::Ok as i32
+    );
+    assert!(unsafe { &*task }.on_check.as_ref().unwrap()(2, 1));
+
+    assert_eq!(
+        run_with(3, Some(double), std::ptr::null_mut(), None),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        run_with(3, None, std::ptr::null_mut(), None),
+        CropsErrorCode::NullPointer as i32
+    );
+
+    // A `Fn` callback may trigger itself again
+    assert_eq!(
+        task_set_on_progress(task, Some(reenter), task as *mut c_void, None),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(task_run(task, 1), CropsErrorCode::Ok as i32);
+    assert_eq!(REENTERED.load(Ordering::SeqCst), CropsErrorCode::Ok as i32);
+
+    unsafe { task_free(task) };
+
+    // A `FnMut` callback triggering itself again fails rather than running twice at once
+    let batch = batch_default();
+    DEPTH.store(0, Ordering::SeqCst);
+    assert_eq!(
+        batch_set_on_finish(batch, Some(reenter_batch), batch as *mut c_void, None),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(batch_run(batch, 1), CropsErrorCode::Ok as i32);
+    assert_eq!(
+        REENTERED.load(Ordering::SeqCst),
+        CropsErrorCode::Panic as i32
+    );
+
+    unsafe { batch_free(batch) };
+
+    let job = job_default();
+    assert_eq!(
+        job_set_on_done(job, Some(count), &COUNT as *const _ as *mut c_void, None),
+        CropsErrorCode::Ok as i32
+    );
+
+    // Calls from several threads at once, which C promised to allow with `c_thread_safe`
+    let on_done = unsafe { &*job }.on_done.clone().unwrap();
+    std::thread::scope(|scope| {
+        for value in 1..=4 {
+            let on_done = on_done.clone();
+            scope.spawn(move || (0..100).for_each(|_| on_done(value)));
+        }
+    });
+    assert_eq!(COUNT.load(Ordering::SeqCst), 1000);
+
+    unsafe { job_free(job) };
+}
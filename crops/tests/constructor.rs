@@ -0,0 +1,41 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+#[c_builder(constructor = (name, weight))]
+struct Brush {
+    weight: u8,
+    name: String,
+}
+
+pub fn main() {
+    let brush = brush_from_name_weight(unsafe { &*c"fine".as_ptr() }, 3);
+    assert!(!brush.is_null());
+    assert_eq!(
+        unsafe { &*brush },
+        &Brush {
+            weight: 3,
+            name: "fine".into()
+        }
+    );
+    unsafe { brush_free(brush) };
+
+    let brush = brush_from_name_weight(unsafe { &*c"\xff".as_ptr() }, 3);
+    assert!(brush.is_null());
+    assert!(crops::error::last_error().unwrap().contains("Brush"));
+
+    let mut out: *mut Brush = std::ptr::null_mut();
+    assert_eq!(
+        brush_try_from_name_weight(unsafe { &*c"\xff".as_ptr() }, 3, out.as_mut_ptr()),
+        CropsErrorCode::InvalidUtf8 as i32
+    );
+    assert!(out.is_null());
+
+    assert_eq!(
+        brush_try_from_name_weight(unsafe { &*c"bold".as_ptr() }, 8, out.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*out }.name, "bold");
+    assert_eq!(unsafe { &*out }.weight, 8);
+    unsafe { brush_free(out) };
+}
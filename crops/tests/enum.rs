@@ -1,3 +1,5 @@
+use crops::traits::AsMutPtr;
+
 #[derive(crops::CBuilder, Debug, Clone, Default, PartialEq, Eq, Copy)]
 enum Color {
     #[default]
@@ -12,4 +14,9 @@ pub fn main() {
     color_as_blue(c);
 
     assert_eq!(unsafe { *c }, Color::Blue);
+
+    let mut tag = ColorTag::Red;
+    color_tag(c, tag.as_mut_ptr());
+
+    assert_eq!(tag, ColorTag::Blue);
 }
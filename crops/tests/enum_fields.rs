@@ -0,0 +1,87 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+enum Shape {
+    #[default]
+    Empty,
+    Circle(f32),
+    Rect {
+        w: f32,
+        h: f32,
+    },
+    Label {
+        text: String,
+    },
+    Move(i32, i32),
+}
+
+pub fn main() {
+    let shape = shape_default();
+
+    assert_eq!(shape_as_rect(shape, 2.0, 3.0), CropsErrorCode::Ok as i32);
+    assert_eq!(unsafe { &*shape }, &Shape::Rect { w: 2.0, h: 3.0 });
+
+    let mut out = 0f32;
+    assert_eq!(
+        shape_get_rect_h(shape, out.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(out, 3.0);
+
+    assert_eq!(shape_as_move(shape, -1, 7), CropsErrorCode::Ok as i32);
+    assert_eq!(unsafe { &*shape }, &Shape::Move(-1, 7));
+
+    let mut coord = 0i32;
+    assert_eq!(
+        shape_get_move_1(shape, coord.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(coord, 7);
+
+    assert_eq!(
+        shape_get_rect_w(shape, out.as_mut_ptr()),
+        CropsErrorCode::VariantMismatch as i32
+    );
+
+    assert_eq!(
+        shape_as_label(shape, unsafe { &*c"hello".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    let mut buffer = vec![0; 8];
+    assert_eq!(
+        shape_get_label_text(
+            shape,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            std::ptr::null_mut()
+        ),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) }.to_str(),
+        Ok("hello")
+    );
+
+    assert_eq!(shape_as_circle(shape, 1.0), CropsErrorCode::Ok as i32);
+    assert_eq!(unsafe { &*shape }, &Shape::Circle(1.0));
+
+    let mut tag = ShapeTag::Empty;
+    assert_eq!(
+        shape_tag(shape, tag.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(tag, ShapeTag::Circle);
+
+    assert_eq!(
+        shape_get_circle(shape, out.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(out, 1.0);
+    assert_eq!(
+        shape_get_move_0(shape, coord.as_mut_ptr()),
+        CropsErrorCode::VariantMismatch as i32
+    );
+
+    unsafe { shape_free(shape) };
+}
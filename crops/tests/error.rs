@@ -0,0 +1,41 @@
+use crops::error::{
+    crops_last_error_clear, crops_last_error_length, crops_last_error_message, CropsErrorCode,
+};
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq, Eq)]
+struct Color {
+    red: u8,
+}
+
+pub fn main() {
+    crops_last_error_clear();
+    assert_eq!(crops_last_error_length(), 0);
+
+    assert_eq!(
+        color_with_red(std::ptr::null_mut(), 0x34),
+        CropsErrorCode::NullPointer as i32
+    );
+
+    let length = crops_last_error_length();
+    assert!(length > 0);
+
+    let mut too_small = vec![0; length - 1];
+    assert_eq!(
+        unsafe { crops_last_error_message(too_small.as_mut_ptr(), too_small.len()) },
+        -1
+    );
+
+    let mut buffer = vec![0; length];
+    let written = unsafe { crops_last_error_message(buffer.as_mut_ptr(), buffer.len()) };
+    assert_eq!(written as usize, length - 1);
+
+    let message = unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) };
+    assert_eq!(
+        message.to_str().unwrap(),
+        crops::error::last_error().unwrap()
+    );
+    assert!(message.to_str().unwrap().contains("Color"));
+
+    crops_last_error_clear();
+    assert_eq!(crops_last_error_length(), 0);
+}
@@ -0,0 +1,64 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+#[c_builder(instantiate(Pair<u32> = PairInt, Pair<String> = PairStr))]
+struct Pair<T> {
+    first: T,
+    second: Vec<T>,
+}
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+#[c_builder(instantiate(Maybe<f32> = MaybeFloat))]
+enum Maybe<T> {
+    #[default]
+    Nothing,
+    Just(T),
+}
+
+pub fn main() {
+    let pair = pair_int_default();
+    assert_eq!(pair_int_with_first(pair, 4), CropsErrorCode::Ok as i32);
+    assert_eq!(pair_int_push_second(pair, 5), CropsErrorCode::Ok as i32);
+    assert_eq!(
+        unsafe { &*pair },
+        &Pair {
+            first: 4,
+            second: vec![5]
+        }
+    );
+    unsafe { pair_int_free(pair) };
+
+    let pair = pair_str_default();
+    assert_eq!(
+        pair_str_with_first(pair, unsafe { &*c"left".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    let mut buffer = vec![0; 8];
+    assert_eq!(
+        pair_str_get_first(
+            pair,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            std::ptr::null_mut()
+        ),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) }.to_str(),
+        Ok("left")
+    );
+    unsafe { pair_str_free(pair) };
+
+    let maybe = maybe_float_default();
+    assert_eq!(maybe_float_as_just(maybe, 2.5), CropsErrorCode::Ok as i32);
+
+    let mut tag = MaybeFloatTag::Nothing;
+    assert_eq!(
+        maybe_float_tag(maybe, tag.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(tag, MaybeFloatTag::Just);
+    assert_eq!(unsafe { &*maybe }, &Maybe::Just(2.5));
+    unsafe { maybe_float_free(maybe) };
+}
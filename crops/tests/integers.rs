@@ -0,0 +1,80 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+use crops::utils::int128::{CropsI128, CropsU128};
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Counters {
+    count: usize,
+    offset: isize,
+    initial: char,
+    big: u128,
+    signed: i128,
+}
+
+pub fn main() {
+    let counters = counters_default();
+
+    assert_eq!(
+        counters_with_count(counters, usize::MAX),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        counters_with_offset(counters, -4),
+        CropsErrorCode::Ok as i32
+    );
+    let mut count = 0usize;
+    assert_eq!(
+        counters_get_count(counters, count.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(count, usize::MAX);
+
+    assert_eq!(
+        counters_with_initial(counters, 'é' as u32),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        counters_with_initial(counters, 0xD800),
+        CropsErrorCode::InvalidChar as i32
+    );
+    let mut initial = 0u32;
+    assert_eq!(
+        counters_get_initial(counters, initial.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(char::from_u32(initial), Some('é'));
+
+    let big = u128::MAX - 1;
+    assert_eq!(
+        counters_with_big(counters, big.into()),
+        CropsErrorCode::Ok as i32
+    );
+    let mut split = CropsU128::default();
+    assert_eq!(
+        counters_get_big(counters, split.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        split,
+        CropsU128 {
+            hi: u64::MAX,
+            lo: u64::MAX - 1
+        }
+    );
+    assert_eq!(u128::from(split), big);
+
+    let signed = i128::MIN + 7;
+    assert_eq!(
+        counters_with_signed(counters, signed.into()),
+        CropsErrorCode::Ok as i32
+    );
+    let mut split = CropsI128::default();
+    assert_eq!(
+        counters_get_signed(counters, split.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(i128::from(split), signed);
+    assert_eq!(i128::from(CropsI128::from(-1i128)), -1);
+
+    unsafe { counters_free(counters) };
+}
@@ -0,0 +1,110 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Scoreboard {
+    scores: HashMap<String, u32>,
+    names: BTreeMap<u32, String>,
+}
+
+fn read(buffer: &[crops::_macros::libc::c_char]) -> &str {
+    unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) }
+        .to_str()
+        .unwrap()
+}
+
+pub fn main() {
+    let board = scoreboard_default();
+
+    assert_eq!(
+        scoreboard_insert_scores(board, unsafe { &*c"alice".as_ptr() }, 3),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        scoreboard_insert_scores(board, unsafe { &*c"bob".as_ptr() }, 5),
+        CropsErrorCode::Ok as i32
+    );
+
+    let mut score = 0u32;
+    assert_eq!(
+        scoreboard_get_scores(board, unsafe { &*c"bob".as_ptr() }, score.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(score, 5);
+    assert_eq!(
+        scoreboard_get_scores(board, unsafe { &*c"carol".as_ptr() }, score.as_mut_ptr()),
+        CropsErrorCode::KeyNotFound as i32
+    );
+
+    let mut contains = false;
+    assert_eq!(
+        scoreboard_contains_scores(board, unsafe { &*c"alice".as_ptr() }, contains.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert!(contains);
+
+    let mut length = 0usize;
+    assert_eq!(
+        scoreboard_len_scores(board, length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(length, 2);
+
+    let mut keys = (0..length)
+        .map(|idx| {
+            let mut buffer = vec![0; 8];
+            assert_eq!(
+                scoreboard_keys_scores(
+                    board,
+                    idx,
+                    buffer.as_mut_ptr(),
+                    buffer.len(),
+                    std::ptr::null_mut()
+                ),
+                CropsErrorCode::Ok as i32
+            );
+            read(&buffer).to_string()
+        })
+        .collect::<Vec<_>>();
+    keys.sort();
+    assert_eq!(keys, vec!["alice", "bob"]);
+    assert_eq!(
+        scoreboard_keys_scores(board, length, std::ptr::null_mut(), 0, std::ptr::null_mut()),
+        CropsErrorCode::IndexOutOfRange as i32
+    );
+
+    assert_eq!(
+        scoreboard_remove_scores(board, unsafe { &*c"alice".as_ptr() }, score.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(score, 3);
+    assert!(!unsafe { &*board }.scores.contains_key("alice"));
+
+    assert_eq!(
+        scoreboard_insert_names(board, 7, unsafe { &*c"seven".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    let mut buffer = vec![0; 8];
+    assert_eq!(
+        scoreboard_get_names(
+            board,
+            7,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            std::ptr::null_mut()
+        ),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(read(&buffer), "seven");
+
+    let mut key = 0u32;
+    assert_eq!(
+        scoreboard_keys_names(board, 0, key.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(key, 7);
+
+    unsafe { scoreboard_free(board) };
+}
@@ -0,0 +1,108 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Config {
+    aliases: Option<Vec<String>>,
+    slots: Vec<Option<u32>>,
+    rows: Vec<Vec<f64>>,
+}
+
+pub fn main() {
+    let config = config_default();
+
+    let mut is_some = true;
+    let mut length = 0usize;
+    assert_eq!(
+        config_is_some_aliases(config, is_some.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert!(!is_some);
+    assert_eq!(
+        config_len_aliases(config, length.as_mut_ptr()),
+        CropsErrorCode::OptionEmpty as i32
+    );
+    assert_eq!(
+        config_push_aliases(config, unsafe { &*c"first".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        config_push_aliases(config, unsafe { &*c"second".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        config_len_aliases(config, length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(length, 2);
+
+    let mut buffer = vec![0; 8];
+    assert_eq!(
+        config_get_aliases(
+            config,
+            1,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            std::ptr::null_mut()
+        ),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) }.to_str(),
+        Ok("second")
+    );
+    assert_eq!(config_clear_aliases(config), CropsErrorCode::Ok as i32);
+    assert_eq!(unsafe { &*config }.aliases, None);
+
+    assert_eq!(config_push_slots(config, 4), CropsErrorCode::Ok as i32);
+    assert_eq!(config_push_none_slots(config), CropsErrorCode::Ok as i32);
+    let mut slot = 0u32;
+    assert_eq!(
+        config_get_slots(config, 1, slot.as_mut_ptr()),
+        CropsErrorCode::OptionEmpty as i32
+    );
+    assert_eq!(config_set_slots(config, 1, 8), CropsErrorCode::Ok as i32);
+    assert_eq!(config_set_none_slots(config, 0), CropsErrorCode::Ok as i32);
+    assert_eq!(
+        config_is_some_slots(config, 1, is_some.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert!(is_some);
+    assert_eq!(unsafe { &*config }.slots, vec![None, Some(8)]);
+
+    assert_eq!(
+        config_push_item_rows(config, 0, 1.0),
+        CropsErrorCode::IndexOutOfRange as i32
+    );
+    assert_eq!(config_push_empty_rows(config), CropsErrorCode::Ok as i32);
+    assert_eq!(
+        config_push_item_rows(config, 0, 1.0),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        config_push_item_rows(config, 0, 2.0),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        config_set_rows(config, 0, 1, 3.0),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        config_item_len_rows(config, 0, length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(length, 2);
+    let mut cell = 0f64;
+    assert_eq!(
+        config_get_rows(config, 0, 1, cell.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(cell, 3.0);
+    assert_eq!(
+        config_len_rows(config, length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(length, 1);
+
+    unsafe { config_free(config) };
+}
@@ -0,0 +1,147 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Report {
+    total: u64,
+}
+
+#[derive(crops::CBuilder, Debug, Clone, Copy, Default, PartialEq)]
+#[c_builder(pod)]
+#[repr(C)]
+struct Span {
+    start: u32,
+    end: u32,
+}
+
+// `CResult` is an alias of `Result<(), E>`, so doesn't take an `out`
+#[crops::c_result_fn]
+fn fails() -> crops::utils::CResult {
+    Err(crops::error::Error::option_empty())
+}
+
+// Known value types and CBuilder types are returned through an `out` without being marked
+#[crops::c_result_fn]
+fn answer() -> u32 {
+    42
+}
+
+#[crops::c_result_fn]
+fn empty_report() -> Report {
+    Report { total: 0 }
+}
+
+#[crops::c_result_fn(by_value)]
+fn span(start: u32, len: u32) -> Result<Span, crops::error::Error> {
+    Ok(Span {
+        start,
+        end: start
+            .checked_add(len)
+            .ok_or_else(crops::error::Error::index_out_of_range)?,
+    })
+}
+
+#[crops::c_result_fn]
+fn checked_double(value: u32) -> Result<u32, String> {
+    value.checked_mul(2).ok_or_else(|| "Overflow".to_string())
+}
+
+#[crops::c_result_fn(out)]
+fn count_words(text: &std::ffi::c_char) -> usize {
+    crops::utils::as_string(text)
+        .unwrap()
+        .split_whitespace()
+        .count()
+}
+
+#[crops::c_result_fn]
+fn greeting(name: &std::ffi::c_char) -> Result<String, crops::error::Error> {
+    Ok(format!("Hello {}", crops::utils::as_string(name)?))
+}
+
+#[crops::c_result_fn]
+fn summarise(values: *const u64, len: usize) -> Result<Report, crops::error::Error> {
+    Ok(Report {
+        total: crops::utils::as_slice(values, len)?.iter().sum(),
+    })
+}
+
+pub fn main() {
+    assert_eq!(fails(), CropsErrorCode::OptionEmpty as i32);
+
+    let mut value = 0u32;
+    assert_eq!(answer(value.as_mut_ptr()), CropsErrorCode::Ok as i32);
+    assert_eq!(value, 42);
+
+    let mut report = std::ptr::null_mut();
+    assert_eq!(empty_report(&mut report), CropsErrorCode::Ok as i32);
+    assert_eq!(unsafe { &*report }, &Report { total: 0 });
+    unsafe { report_free(report) };
+
+    let mut range = Span::default();
+    assert_eq!(span(2, 3, range.as_mut_ptr()), CropsErrorCode::Ok as i32);
+    assert_eq!(range, Span { start: 2, end: 5 });
+    assert_eq!(
+        span(u32::MAX, 1, range.as_mut_ptr()),
+        CropsErrorCode::IndexOutOfRange as i32
+    );
+    assert_eq!(range, Span { start: 2, end: 5 });
+
+    let mut doubled = 0u32;
+    assert_eq!(
+        checked_double(21, doubled.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(doubled, 42);
+    assert_eq!(
+        checked_double(u32::MAX, doubled.as_mut_ptr()),
+        CropsErrorCode::Unknown as i32
+    );
+    assert_eq!(
+        checked_double(1, std::ptr::null_mut()),
+        CropsErrorCode::NullPointer as i32
+    );
+
+    let mut words = 0usize;
+    assert_eq!(
+        count_words(unsafe { &*c"one two three".as_ptr() }, words.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(words, 3);
+
+    let mut length = 0usize;
+    let mut buffer = vec![0; 8];
+    assert_eq!(
+        greeting(
+            unsafe { &*c"there".as_ptr() },
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            length.as_mut_ptr()
+        ),
+        CropsErrorCode::BufferTooSmall as i32
+    );
+    assert_eq!(length, 11);
+    let mut buffer = vec![0; length + 1];
+    assert_eq!(
+        greeting(
+            unsafe { &*c"there".as_ptr() },
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            std::ptr::null_mut()
+        ),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) }.to_str(),
+        Ok("Hello there")
+    );
+
+    let values = [1u64, 2, 3];
+    let mut report = std::ptr::null_mut();
+    assert_eq!(
+        summarise(values.as_ptr(), values.len(), &mut report),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*report }, &Report { total: 6 });
+    unsafe { report_free(report) };
+}
@@ -0,0 +1,47 @@
+use crops::error::CropsErrorCode;
+use crops::utils::crops_string_free;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Brush {
+    #[c_builder(c_owned)]
+    name: String,
+    #[c_builder(c_owned)]
+    aliases: Vec<String>,
+    #[c_builder(c_owned)]
+    nickname: Option<String>,
+}
+
+fn read(s: *mut crops::_macros::libc::c_char) -> String {
+    assert!(!s.is_null());
+    let res = unsafe { std::ffi::CStr::from_ptr(s) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    unsafe { crops_string_free(s) };
+    res
+}
+
+pub fn main() {
+    let brush = brush_default();
+    brush_with_name(brush, unsafe { &*c"a rather long brush name".as_ptr() });
+    brush_push_aliases(brush, unsafe { &*c"circle".as_ptr() });
+
+    assert_eq!(
+        read(brush_get_owned_name(brush)),
+        "a rather long brush name"
+    );
+    assert_eq!(read(brush_get_owned_aliases(brush, 0)), "circle");
+
+    assert!(brush_get_owned_aliases(brush, 1).is_null());
+    assert!(brush_get_owned_nickname(brush).is_null());
+    assert!(brush_get_owned_name(std::ptr::null()).is_null());
+
+    assert_eq!(
+        brush_replace_nickname(brush, unsafe { &*c"roundy".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(read(brush_get_owned_nickname(brush)), "roundy");
+
+    unsafe { crops_string_free(std::ptr::null_mut()) };
+    unsafe { brush_free(brush) };
+}
@@ -0,0 +1,36 @@
+use crops::error::CropsErrorCode;
+
+#[derive(crops::CBuilder, Debug, Default)]
+struct Brush {
+    weight: u8,
+}
+
+impl Clone for Brush {
+    fn clone(&self) -> Self {
+        panic!("Brushes cannot be cloned")
+    }
+}
+
+#[crops::c_result_fn]
+fn brush_explode(_source: *mut Brush) -> crops::utils::CResult {
+    panic!("Boom")
+}
+
+pub fn main() {
+    std::panic::set_hook(Box::new(|_| {}));
+
+    let brush = brush_default();
+
+    assert!(unsafe { brush_clone(&*brush) }.is_null());
+    assert_eq!(
+        crops::error::last_error().unwrap(),
+        "Rust panicked: Brushes cannot be cloned"
+    );
+
+    assert_eq!(brush_explode(brush), CropsErrorCode::Panic as i32);
+    assert_eq!(crops::error::last_error().unwrap(), "Rust panicked: Boom");
+
+    assert_eq!(brush_with_weight(brush, 3), CropsErrorCode::Ok as i32);
+
+    unsafe { brush_free(brush) };
+}
@@ -0,0 +1,97 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Copy, Default, PartialEq)]
+#[c_builder(pod)]
+#[repr(C)]
+struct Point {
+    x: f32,
+    y: f32,
+}
+
+#[derive(crops::CBuilder, Debug, Clone, Copy, Default, PartialEq)]
+#[c_builder(pod)]
+#[repr(C)]
+struct Segment {
+    #[c_builder(c_by_value)]
+    start: Point,
+    #[c_builder(c_by_value)]
+    end: Point,
+    closed: bool,
+}
+
+#[derive(crops::CBuilder, Debug, Clone, Copy, Default, PartialEq)]
+#[c_builder(int_enum)]
+#[repr(C)]
+enum Level {
+    #[default]
+    Low = 1,
+    High = 2,
+}
+
+#[derive(crops::CBuilder, Debug, Clone, Copy, Default, PartialEq)]
+#[c_builder(pod)]
+#[repr(C)]
+struct Reading {
+    #[c_builder(c_by_value)]
+    level: Level,
+    value: f32,
+}
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Shape {
+    #[c_builder(c_by_value)]
+    origin: Point,
+    #[c_builder(c_by_value)]
+    outline: Vec<Point>,
+}
+
+#[crops::c_result_fn]
+fn shape_translate(shape: *mut Shape, by: Point) -> crops::utils::CResult {
+    let shape = crops::utils::check_null(shape)?;
+    shape.origin.x += by.x;
+    shape.origin.y += by.y;
+
+    Ok(())
+}
+
+pub fn main() {
+    assert_eq!(point_default(), Point { x: 0.0, y: 0.0 });
+    assert_eq!(segment_default().end, Point::default());
+
+    // Level has no 0 discriminant, so the value returned after a panic is built field by field
+    assert_eq!(
+        <Reading as crops::traits::ByValue>::zeroed(),
+        Reading {
+            level: Level::Low,
+            value: 0.0
+        }
+    );
+    assert_eq!(reading_default().level, Level::Low);
+
+    let shape = shape_default();
+    assert_eq!(
+        shape_with_origin(shape, Point { x: 1.0, y: 2.0 }),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        shape_translate(shape, Point { x: 1.0, y: 1.0 }),
+        CropsErrorCode::Ok as i32
+    );
+
+    let mut origin = point_default();
+    assert_eq!(
+        shape_get_origin(shape, origin.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(origin, Point { x: 2.0, y: 3.0 });
+
+    let outline = [Point { x: 0.0, y: 0.0 }, Point { x: 4.0, y: 0.0 }];
+    assert_eq!(
+        shape_extend_outline(shape, outline.as_ptr(), outline.len()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*shape }.outline, outline.to_vec());
+
+    unsafe { shape_free(shape) };
+}
@@ -0,0 +1,88 @@
+use std::collections::{BTreeSet, HashSet};
+
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Record {
+    tags: HashSet<String>,
+    ids: BTreeSet<u64>,
+}
+
+pub fn main() {
+    let record = record_default();
+
+    assert_eq!(
+        record_insert_tags(record, unsafe { &*c"red".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        record_insert_tags(record, unsafe { &*c"red".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        record_insert_tags(record, unsafe { &*c"blue".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+
+    let mut length = 0usize;
+    assert_eq!(
+        record_len_tags(record, length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(length, 2);
+
+    let mut contains = false;
+    assert_eq!(
+        record_contains_tags(record, unsafe { &*c"blue".as_ptr() }, contains.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert!(contains);
+
+    let mut tags = (0..length)
+        .map(|idx| {
+            let mut buffer = vec![0; 8];
+            assert_eq!(
+                record_get_tags(
+                    record,
+                    idx,
+                    buffer.as_mut_ptr(),
+                    buffer.len(),
+                    std::ptr::null_mut()
+                ),
+                CropsErrorCode::Ok as i32
+            );
+            unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) }
+                .to_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect::<Vec<_>>();
+    tags.sort();
+    assert_eq!(tags, vec!["blue", "red"]);
+
+    assert_eq!(
+        record_remove_tags(record, unsafe { &*c"red".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        record_remove_tags(record, unsafe { &*c"red".as_ptr() }),
+        CropsErrorCode::KeyNotFound as i32
+    );
+
+    for id in [9, 3, 6] {
+        assert_eq!(record_insert_ids(record, id), CropsErrorCode::Ok as i32);
+    }
+    let mut id = 0u64;
+    assert_eq!(
+        record_get_ids(record, 0, id.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(id, 3);
+    assert_eq!(
+        record_get_ids(record, 3, id.as_mut_ptr()),
+        CropsErrorCode::IndexOutOfRange as i32
+    );
+
+    unsafe { record_free(record) };
+}
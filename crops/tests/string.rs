@@ -0,0 +1,116 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Brush {
+    name: String,
+    aliases: Vec<String>,
+    nickname: Option<String>,
+}
+
+pub fn main() {
+    let brush = brush_default();
+    brush_with_name(brush, unsafe { &*c"round".as_ptr() });
+    brush_push_aliases(brush, unsafe { &*c"circle".as_ptr() });
+    brush_replace_nickname(brush, unsafe { &*c"roundy".as_ptr() });
+
+    // Query the length only
+    let mut length = 0usize;
+    assert_eq!(
+        brush_get_name(brush, std::ptr::null_mut(), 0, length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(length, 5);
+
+    // Too small for the NUL terminator, so as much as fits is copied
+    let mut buffer = vec![0x7f; length];
+    assert_eq!(
+        brush_get_name(
+            brush,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            std::ptr::null_mut()
+        ),
+        CropsErrorCode::BufferTooSmall as i32
+    );
+    assert_eq!(
+        unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) }.to_str(),
+        Ok("roun")
+    );
+
+    let mut empty: std::ffi::c_char = 0x7f;
+    assert_eq!(
+        brush_get_name(brush, empty.as_mut_ptr(), 1, std::ptr::null_mut()),
+        CropsErrorCode::BufferTooSmall as i32
+    );
+    assert_eq!(empty, 0);
+
+    let mut buffer = vec![0x7f; length + 1];
+    assert_eq!(
+        brush_get_name(
+            brush,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            std::ptr::null_mut()
+        ),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) }.to_str(),
+        Ok("round")
+    );
+
+    // Failed or queried removals leave the value in place
+    let mut small = vec![0; 3];
+    assert_eq!(
+        brush_remove_aliases(
+            brush,
+            0,
+            small.as_mut_ptr(),
+            small.len(),
+            length.as_mut_ptr()
+        ),
+        CropsErrorCode::BufferTooSmall as i32
+    );
+    assert_eq!(length, 6);
+    assert_eq!(
+        brush_remove_aliases(brush, 0, std::ptr::null_mut(), 0, length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*brush }.aliases.len(), 1);
+
+    let mut buffer = vec![0; 16];
+    assert_eq!(
+        brush_remove_aliases(
+            brush,
+            0,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            std::ptr::null_mut()
+        ),
+        CropsErrorCode::Ok as i32
+    );
+    assert!(unsafe { &*brush }.aliases.is_empty());
+
+    assert_eq!(
+        brush_take_nickname(brush, small.as_mut_ptr(), small.len(), std::ptr::null_mut()),
+        CropsErrorCode::BufferTooSmall as i32
+    );
+    assert!(unsafe { &*brush }.nickname.is_some());
+    assert_eq!(
+        brush_take_nickname(
+            brush,
+            buffer.as_mut_ptr(),
+            buffer.len(),
+            std::ptr::null_mut()
+        ),
+        CropsErrorCode::Ok as i32
+    );
+    assert!(unsafe { &*brush }.nickname.is_none());
+    assert_eq!(
+        unsafe { std::ffi::CStr::from_ptr(buffer.as_ptr()) }.to_str(),
+        Ok("roundy")
+    );
+
+    unsafe { brush_free(brush) };
+}
@@ -0,0 +1,80 @@
+use crops::error::CropsErrorCode;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Brush {
+    name: String,
+}
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Canvas {
+    #[c_builder(c_take)]
+    brush: Brush,
+}
+
+// Fields don't need to implement `Default` to be moved in or swapped
+#[derive(crops::CBuilder, Debug, Clone, PartialEq)]
+#[c_builder(new = false)]
+struct Stamp(u64);
+
+#[derive(crops::CBuilder, Debug, Clone, PartialEq)]
+#[c_builder(new = false)]
+struct Receipt {
+    stamp: Stamp,
+}
+
+pub fn main() {
+    let canvas = canvas_default();
+
+    let brush = brush_default();
+    brush_with_name(brush, c"round".as_ptr());
+    assert_eq!(
+        canvas_set_brush_take(std::ptr::null_mut(), brush),
+        CropsErrorCode::NullPointer as i32
+    );
+    assert_eq!(
+        canvas_set_brush_take(canvas, brush),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*canvas }.brush.name, "round");
+    assert_eq!(
+        canvas_set_brush_take(canvas, std::ptr::null_mut()),
+        CropsErrorCode::NullPointer as i32
+    );
+
+    let mut taken = std::ptr::null_mut();
+    assert_eq!(
+        canvas_take_brush(canvas, &mut taken),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*taken }.name, "round");
+    assert_eq!(unsafe { &*canvas }.brush, Brush::default());
+    assert_eq!(
+        canvas_take_brush(canvas, std::ptr::null_mut()),
+        CropsErrorCode::NullPointer as i32
+    );
+
+    assert_eq!(canvas_swap_brush(canvas, taken), CropsErrorCode::Ok as i32);
+    assert_eq!(unsafe { &*canvas }.brush.name, "round");
+    assert_eq!(unsafe { &*taken }, &Brush::default());
+
+    unsafe { brush_free(taken) };
+    unsafe { canvas_free(canvas) };
+
+    let receipt = Box::into_raw(Box::new(Receipt { stamp: Stamp(1) }));
+    assert_eq!(
+        receipt_set_stamp_take(receipt, Box::into_raw(Box::new(Stamp(2)))),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*receipt }.stamp, Stamp(2));
+
+    let taken = Box::into_raw(Box::new(Stamp(3)));
+    assert_eq!(
+        receipt_swap_stamp(receipt, taken),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(unsafe { &*taken }, &Stamp(2));
+    assert_eq!(unsafe { &*receipt }.stamp, Stamp(3));
+
+    unsafe { stamp_free(taken) };
+    unsafe { receipt_free(receipt) };
+}
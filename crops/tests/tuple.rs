@@ -0,0 +1,44 @@
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Copy, Default, PartialEq)]
+#[c_builder(transparent)]
+#[repr(transparent)]
+struct Meters(f64);
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Span(u32, #[c_builder(c_name = label)] String);
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Empty;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Ruler {
+    #[c_builder(c_by_value)]
+    length: Meters,
+}
+
+pub fn main() {
+    let span = span_default();
+    span_with_0(span, 42);
+    span_with_label(span, unsafe { &*c"inches".as_ptr() });
+
+    let mut out = 0u32;
+    span_get_0(span, out.as_mut_ptr());
+    assert_eq!(out, 42);
+    assert_eq!(unsafe { &*span }, &Span(42, "inches".into()));
+    unsafe { span_free(span) };
+
+    let empty = empty_default();
+    assert_eq!(unsafe { &*empty }, &Empty);
+    unsafe { empty_free(empty) };
+
+    assert_eq!(meters_default(), Meters(0.0));
+
+    let ruler = ruler_default();
+    ruler_with_length(ruler, Meters(1.5));
+
+    let mut length = Meters(0.0);
+    ruler_get_length(ruler, length.as_mut_ptr());
+    assert_eq!(length, Meters(1.5));
+    unsafe { ruler_free(ruler) };
+}
@@ -0,0 +1,80 @@
+use crops::error::CropsErrorCode;
+use crops::traits::AsMutPtr;
+
+#[derive(crops::CBuilder, Debug, Clone, Default, PartialEq)]
+struct Series {
+    values: Vec<i32>,
+    labels: Vec<String>,
+}
+
+pub fn main() {
+    let series = series_default();
+
+    let values = [1, 2, 3, 4];
+    assert_eq!(
+        series_extend_values(series, values.as_ptr(), values.len()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        series_insert_values(series, 0, 0),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        series_insert_values(series, 5, 5),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        series_insert_values(series, 7, 7),
+        CropsErrorCode::IndexOutOfRange as i32
+    );
+    assert_eq!(series_set_values(series, 1, 10), CropsErrorCode::Ok as i32);
+    assert_eq!(
+        series_set_values(series, 6, 10),
+        CropsErrorCode::IndexOutOfRange as i32
+    );
+
+    let mut length = 0usize;
+    assert_eq!(
+        series_len_values(series, length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(length, 6);
+
+    let mut buffer = vec![0; 4];
+    assert_eq!(
+        series_copy_values(series, buffer.as_mut_ptr(), buffer.len()),
+        CropsErrorCode::BufferTooSmall as i32
+    );
+    assert_eq!(series_truncate_values(series, 4), CropsErrorCode::Ok as i32);
+    assert_eq!(
+        series_copy_values(series, buffer.as_mut_ptr(), buffer.len()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(buffer, vec![0, 10, 2, 3]);
+
+    assert_eq!(series_clear_values(series), CropsErrorCode::Ok as i32);
+    assert_eq!(
+        series_len_values(series, length.as_mut_ptr()),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(length, 0);
+
+    assert_eq!(
+        series_push_labels(series, unsafe { &*c"b".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        series_insert_labels(series, 0, unsafe { &*c"a".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        series_set_labels(series, 1, unsafe { &*c"c".as_ptr() }),
+        CropsErrorCode::Ok as i32
+    );
+    assert_eq!(
+        unsafe { &*series }.labels,
+        vec!["a".to_string(), "c".to_string()]
+    );
+
+    unsafe { series_free(series) };
+}